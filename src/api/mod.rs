@@ -0,0 +1,259 @@
+mod recent;
+mod stream;
+
+pub use recent::RecentTransfers;
+pub use stream::channel as stream_channel;
+
+use crate::cli::parse_time;
+use crate::config::ApiConfig;
+use crate::status::StatusRegistry;
+use crate::store::{SortKey, SqliteStore, TransferQuery};
+use crate::types::{Chain, WhaleTransfer};
+
+use alloy::primitives::Address;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use eyre::Result;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Largest page size accepted by `/transfers`
+const MAX_PAGE_SIZE: usize = 1000;
+
+/// Shared state behind the HTTP handlers
+#[derive(Clone)]
+pub struct ApiState {
+    pub recent: Arc<RecentTransfers>,
+    pub status: Arc<StatusRegistry>,
+    pub store: Option<SqliteStore>,
+    pub started: Instant,
+}
+
+/// Build the API router
+pub fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/transfers", get(transfers))
+        .route("/status", get(status))
+        .route("/stats", get(stats))
+        .route("/stream/sse", get(stream::sse))
+        .route("/stream/ws", get(stream::ws))
+        .with_state(state)
+}
+
+/// Serve the API until the process exits
+pub async fn serve(config: &ApiConfig, state: ApiState) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(&config.listen).await?;
+    tracing::info!(addr = %listener.local_addr()?, "REST API listening");
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
+
+/// Error returned as `{"error": "..."}`
+#[derive(Debug)]
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+async fn health(State(state): State<ApiState>) -> Json<serde_json::Value> {
+    Json(json!({
+        "status": "ok",
+        "uptime_secs": state.started.elapsed().as_secs(),
+    }))
+}
+
+async fn status(State(state): State<ApiState>) -> Json<serde_json::Value> {
+    let chains: serde_json::Map<String, serde_json::Value> = state
+        .status
+        .snapshot()
+        .into_iter()
+        .map(|(chain, status)| {
+            let mut value = json!(status);
+            value["lag"] = json!(status.lag());
+            (chain.name().to_lowercase(), value)
+        })
+        .collect();
+    Json(json!({ "chains": chains }))
+}
+
+async fn stats(State(state): State<ApiState>) -> Json<serde_json::Value> {
+    let totals = state.recent.totals();
+    let count: u64 = totals.values().map(|t| t.count).sum();
+    let volume: f64 = totals.values().map(|t| t.volume_usd).sum();
+    let chains: serde_json::Map<String, serde_json::Value> = totals
+        .into_iter()
+        .map(|(chain, totals)| (chain.name().to_lowercase(), json!(totals)))
+        .collect();
+
+    Json(json!({
+        "uptime_secs": state.started.elapsed().as_secs(),
+        "count": count,
+        "volume_usd": volume,
+        "chains": chains,
+    }))
+}
+
+/// Query string for `/transfers`
+#[derive(Debug, Default, Deserialize)]
+struct TransfersParams {
+    /// Comma-separated chain names
+    chain: Option<String>,
+    address: Option<Address>,
+    label: Option<String>,
+    min_amount: Option<f64>,
+    max_amount: Option<f64>,
+    since: Option<String>,
+    until: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+impl TransfersParams {
+    fn to_query(&self) -> Result<TransferQuery, String> {
+        let chains = match &self.chain {
+            Some(list) => list
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<Chain>, _>>()?,
+            None => Vec::new(),
+        };
+        let time = |value: &Option<String>| value.as_deref().map(parse_time).transpose();
+
+        Ok(TransferQuery {
+            chains,
+            address: self.address,
+            label: self.label.clone(),
+            min_amount_usd: self.min_amount,
+            max_amount_usd: self.max_amount,
+            since: time(&self.since)?,
+            until: time(&self.until)?,
+            sort: SortKey::Time,
+            ascending: false,
+            limit: Some(self.limit.unwrap_or(50).min(MAX_PAGE_SIZE)),
+            offset: self.offset.unwrap_or(0),
+        })
+    }
+}
+
+async fn transfers(
+    State(state): State<ApiState>,
+    Query(params): Query<TransfersParams>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let query = params
+        .to_query()
+        .map_err(|e| ApiError(StatusCode::BAD_REQUEST, e))?;
+    let limit = query.limit.unwrap_or_default();
+
+    let (source, transfers) = match &state.store {
+        Some(store) => {
+            let store = store.clone();
+            let q = query.clone();
+            let transfers = tokio::task::spawn_blocking(move || store.query(&q))
+                .await
+                .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+                .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            ("history", transfers)
+        }
+        None => (
+            "memory",
+            state
+                .recent
+                .newest(query.offset, limit, |t| matches_query(&query, t)),
+        ),
+    };
+
+    Ok(Json(json!({
+        "source": source,
+        "offset": query.offset,
+        "limit": limit,
+        "transfers": transfers,
+    })))
+}
+
+/// In-memory equivalent of the history store's query filters
+fn matches_query(query: &TransferQuery, transfer: &WhaleTransfer) -> bool {
+    let label_matches = |label: &Option<String>, needle: &str| {
+        label
+            .as_deref()
+            .is_some_and(|l| l.to_lowercase().contains(&needle.to_lowercase()))
+    };
+    let time = transfer.block_timestamp;
+    let before = |bound: DateTime<Utc>| time.is_none_or(|t| t < bound);
+
+    (query.chains.is_empty() || query.chains.contains(&transfer.chain))
+        && query
+            .address
+            .is_none_or(|a| transfer.from == a || transfer.to == a)
+        && query.label.as_deref().is_none_or(|needle| {
+            label_matches(&transfer.from_label, needle) || label_matches(&transfer.to_label, needle)
+        })
+        && query.min_amount_usd.is_none_or(|min| transfer.amount_usd >= min)
+        && query.max_amount_usd.is_none_or(|max| transfer.amount_usd <= max)
+        && query.since.is_none_or(|since| !before(since))
+        && query.until.is_none_or(before)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{B256, U256};
+
+    #[tokio::test]
+    async fn test_transfers_from_memory() {
+        let recent = Arc::new(RecentTransfers::new(2, stream_channel(8)));
+        for (i, chain) in [Chain::Ethereum, Chain::Base, Chain::Base].into_iter().enumerate() {
+            recent.push(WhaleTransfer::new(
+                chain,
+                B256::repeat_byte(i as u8),
+                i as u64,
+                Address::repeat_byte(1),
+                Address::repeat_byte(2),
+                U256::from(1_000_000_000_000u64 * (i as u64 + 1)),
+            ));
+        }
+        let state = ApiState {
+            recent,
+            status: Arc::new(StatusRegistry::new()),
+            store: None,
+            started: Instant::now(),
+        };
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await });
+
+        let body: serde_json::Value = reqwest::get(format!("http://{}/transfers?chain=base&limit=1", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body["source"], "memory");
+        assert_eq!(body["transfers"].as_array().unwrap().len(), 1);
+        assert_eq!(body["transfers"][0]["block_number"], 2);
+
+        let stats: serde_json::Value = reqwest::get(format!("http://{}/stats", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(stats["count"], 3);
+        assert_eq!(stats["chains"]["base"]["count"], 2);
+
+        let bad = reqwest::get(format!("http://{}/transfers?chain=solana", addr))
+            .await
+            .unwrap();
+        assert_eq!(bad.status(), 400);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::filter::TransferFilter;
+use crate::notify::Notifier;
+use crate::types::{Chain, WhaleTransfer};
+
+use async_trait::async_trait;
+use eyre::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// In-memory ring buffer of the most recent transfers plus running totals
+///
+/// Every pushed transfer is also broadcast to live stream subscribers.
+pub struct RecentTransfers {
+    capacity: usize,
+    inner: Mutex<Inner>,
+    live: broadcast::Sender<WhaleTransfer>,
+    filter: TransferFilter,
+}
+
+#[derive(Default)]
+struct Inner {
+    transfers: VecDeque<WhaleTransfer>,
+    totals: BTreeMap<Chain, ChainTotals>,
+}
+
+/// Transfers seen on one chain since startup
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChainTotals {
+    pub count: u64,
+    pub volume_usd: f64,
+    pub largest_usd: f64,
+}
+
+impl RecentTransfers {
+    /// Create a buffer keeping at most `capacity` transfers
+    pub fn new(capacity: usize, live: broadcast::Sender<WhaleTransfer>) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            inner: Mutex::new(Inner::default()),
+            live,
+            filter: TransferFilter::default(),
+        }
+    }
+
+    /// Add a transfer, evicting the oldest when full
+    pub fn push(&self, transfer: WhaleTransfer) {
+        let mut inner = self.inner.lock().expect("recent lock poisoned");
+
+        let totals = inner.totals.entry(transfer.chain).or_default();
+        totals.count += 1;
+        totals.volume_usd += transfer.amount_usd;
+        totals.largest_usd = totals.largest_usd.max(transfer.amount_usd);
+
+        if inner.transfers.len() == self.capacity {
+            inner.transfers.pop_front();
+        }
+        inner.transfers.push_back(transfer.clone());
+
+        // No subscribers is not an error
+        let _ = self.live.send(transfer);
+    }
+
+    /// Subscribe to new transfers, also returning up to `replay` of the latest
+    /// ones (oldest first) with no gap or overlap between the two
+    pub fn subscribe(&self, replay: usize) -> (Vec<WhaleTransfer>, broadcast::Receiver<WhaleTransfer>) {
+        let inner = self.inner.lock().expect("recent lock poisoned");
+        let skip = inner.transfers.len().saturating_sub(replay);
+        let replayed = inner.transfers.iter().skip(skip).cloned().collect();
+        (replayed, self.live.subscribe())
+    }
+
+    /// Newest-first transfers matching a predicate, after skipping `offset`
+    pub fn newest(
+        &self,
+        offset: usize,
+        limit: usize,
+        predicate: impl Fn(&WhaleTransfer) -> bool,
+    ) -> Vec<WhaleTransfer> {
+        let inner = self.inner.lock().expect("recent lock poisoned");
+        inner
+            .transfers
+            .iter()
+            .rev()
+            .filter(|t| predicate(t))
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Totals per chain since startup
+    pub fn totals(&self) -> BTreeMap<Chain, ChainTotals> {
+        self.inner.lock().expect("recent lock poisoned").totals.clone()
+    }
+}
+
+#[async_trait]
+impl Notifier for RecentTransfers {
+    fn name(&self) -> &str {
+        "recent"
+    }
+
+    fn filter(&self) -> &TransferFilter {
+        &self.filter
+    }
+
+    async fn notify(&self, transfer: &WhaleTransfer) -> Result<()> {
+        self.push(transfer.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,225 @@
+use super::{matches_query, ApiError, ApiState};
+use crate::store::TransferQuery;
+use crate::types::{Chain, WhaleTransfer};
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::Response;
+use futures::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+use serde_json::json;
+use std::convert::Infallible;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// Query string for the live streams
+#[derive(Debug, Default, Deserialize)]
+pub struct StreamParams {
+    /// Comma-separated chain names
+    chain: Option<String>,
+    min_amount: Option<f64>,
+    label: Option<String>,
+    /// Number of recent transfers to send on connect
+    replay: Option<usize>,
+}
+
+impl StreamParams {
+    fn to_query(&self) -> Result<TransferQuery, String> {
+        let chains = match &self.chain {
+            Some(list) => list
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<Chain>, _>>()?,
+            None => Vec::new(),
+        };
+
+        Ok(TransferQuery {
+            chains,
+            label: self.label.clone(),
+            min_amount_usd: self.min_amount,
+            ..TransferQuery::default()
+        })
+    }
+}
+
+/// Item delivered to a stream client
+enum Item {
+    Transfer(Box<WhaleTransfer>),
+    /// The client fell behind and this many transfers were skipped
+    Lagged(u64),
+}
+
+/// Subscribe with replay and filtering, yielding items in order
+fn subscribe(
+    state: &ApiState,
+    params: &StreamParams,
+) -> Result<impl Stream<Item = Item> + Send + 'static, ApiError> {
+    let query = params
+        .to_query()
+        .map_err(|e| ApiError(StatusCode::BAD_REQUEST, e))?;
+    let (replay, rx) = state.recent.subscribe(params.replay.unwrap_or(0));
+    let replay: Vec<Item> = replay
+        .into_iter()
+        .filter(|t| matches_query(&query, t))
+        .map(|transfer| Item::Transfer(Box::new(transfer)))
+        .collect();
+
+    let live = stream::unfold((rx, query), |(mut rx, query)| async move {
+        loop {
+            match rx.recv().await {
+                Ok(transfer) if matches_query(&query, &transfer) => {
+                    return Some((Item::Transfer(Box::new(transfer)), (rx, query)));
+                }
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    return Some((Item::Lagged(skipped), (rx, query)));
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Ok(stream::iter(replay).chain(live))
+}
+
+/// `GET /stream/sse`: Server-Sent Events with `transfer` and `lagged` events
+pub async fn sse(
+    State(state): State<ApiState>,
+    Query(params): Query<StreamParams>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let events = subscribe(&state, &params)?.map(|item| {
+        let event = match item {
+            Item::Transfer(transfer) => Event::default()
+                .event("transfer")
+                .json_data(&transfer)
+                .unwrap_or_else(|_| Event::default().event("error")),
+            Item::Lagged(skipped) => Event::default()
+                .event("lagged")
+                .data(json!({ "skipped": skipped }).to_string()),
+        };
+        Ok(event)
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// `GET /stream/ws`: WebSocket with one JSON text message per event
+pub async fn ws(
+    State(state): State<ApiState>,
+    Query(params): Query<StreamParams>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let items = subscribe(&state, &params)?;
+    Ok(upgrade.on_upgrade(move |socket| forward(socket, items)))
+}
+
+async fn forward(mut socket: WebSocket, items: impl Stream<Item = Item> + Send + 'static) {
+    let mut items = Box::pin(items);
+    loop {
+        tokio::select! {
+            item = items.next() => {
+                let Some(item) = item else { break };
+                let text = match item {
+                    Item::Transfer(transfer) => json!({ "type": "transfer", "transfer": transfer }),
+                    Item::Lagged(skipped) => json!({ "type": "lagged", "skipped": skipped }),
+                };
+                if socket.send(Message::Text(text.to_string().into())).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => {
+                // Clients don't send anything meaningful; stop when they go away
+                match incoming {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Create the broadcast channel backing the live streams
+pub fn channel(buffer: usize) -> broadcast::Sender<WhaleTransfer> {
+    broadcast::channel(buffer.max(1)).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{router, RecentTransfers};
+    use crate::status::StatusRegistry;
+    use alloy::primitives::{Address, B256, U256};
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    fn transfer(chain: Chain, block: u64) -> WhaleTransfer {
+        WhaleTransfer::new(
+            chain,
+            B256::repeat_byte(block as u8),
+            block,
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            U256::from(5_000_000_000_000u64),
+        )
+    }
+
+    fn state(buffer: usize) -> ApiState {
+        ApiState {
+            recent: Arc::new(RecentTransfers::new(10, channel(buffer))),
+            status: Arc::new(StatusRegistry::new()),
+            store: None,
+            started: Instant::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sse_replays_then_streams() {
+        let state = state(8);
+        state.recent.push(transfer(Chain::Base, 1));
+        state.recent.push(transfer(Chain::Ethereum, 2));
+        state.recent.push(transfer(Chain::Base, 3));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let recent = Arc::clone(&state.recent);
+        tokio::spawn(async move { axum::serve(listener, router(state)).await });
+
+        let mut response = reqwest::get(format!("http://{}/stream/sse?chain=base&replay=2", addr))
+            .await
+            .unwrap();
+        recent.push(transfer(Chain::Ethereum, 4));
+        recent.push(transfer(Chain::Base, 5));
+
+        let mut body = String::new();
+        while body.matches("event: transfer").count() < 2 {
+            let chunk = response.chunk().await.unwrap().unwrap();
+            body.push_str(&String::from_utf8_lossy(&chunk));
+        }
+        // Replay covers blocks 2 and 3, of which only 3 is on Base; then live block 5
+        let blocks: Vec<&str> = body
+            .lines()
+            .filter_map(|l| l.strip_prefix("data: "))
+            .collect();
+        assert!(blocks[0].contains("\"block_number\":3"));
+        assert!(blocks[1].contains("\"block_number\":5"));
+    }
+
+    #[tokio::test]
+    async fn test_slow_client_gets_lagged_event() {
+        let state = state(2);
+        let mut items = Box::pin(subscribe(&state, &StreamParams::default()).unwrap());
+        for block in 0..5 {
+            state.recent.push(transfer(Chain::Base, block));
+        }
+
+        match items.next().await.unwrap() {
+            Item::Lagged(skipped) => assert_eq!(skipped, 3),
+            Item::Transfer(_) => panic!("expected lag notice"),
+        }
+        match items.next().await.unwrap() {
+            Item::Transfer(t) => assert_eq!(t.block_number, 3),
+            Item::Lagged(_) => panic!("expected transfer"),
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use crate::config::ClassifyConfig;
+use crate::types::Chain;
+
+use alloy::primitives::{hex, Address};
+use alloy::providers::Provider;
+use alloy::transports::Transport;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// EOAs can receive code later (counterfactual Safes, EIP-7702), so they are rechecked
+const EOA_RECHECK_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// How long new classifications are collected before the cache file is rewritten
+const SAVE_DELAY: Duration = Duration::from_secs(5);
+
+/// Cache size cap; the least recently checked entries are dropped beyond it
+const MAX_ENTRIES: usize = 200_000;
+
+/// Runtime code of an EIP-1167 minimal proxy, around the 20-byte implementation address
+const EIP1167_PREFIX: [u8; 10] = hex!("363d3d373d3d3d363d73");
+const EIP1167_SUFFIX: [u8; 15] = hex!("5af43d82803e903d91602b57fd5bf3");
+
+/// Code of an EOA delegating to a contract under EIP-7702
+const EIP7702_PREFIX: [u8; 3] = hex!("ef0100");
+
+/// `masterCopy()` selector pushed by every Gnosis Safe proxy to answer it without a delegatecall
+const SAFE_MASTER_COPY: [u8; 5] = hex!("7fa619486e");
+
+/// What kind of account an address is, judged from its code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressKind {
+    /// Externally owned account, including EIP-7702 delegated ones
+    Eoa,
+    /// Gnosis Safe proxy
+    Safe,
+    /// EIP-1167 minimal proxy clone
+    Clone,
+    /// Any other contract
+    Contract,
+}
+
+impl AddressKind {
+    /// Classify runtime bytecode
+    pub fn from_code(code: &[u8]) -> Self {
+        if code.is_empty() || (code.len() == 23 && code.starts_with(&EIP7702_PREFIX)) {
+            AddressKind::Eoa
+        } else if code.len() == 45
+            && code.starts_with(&EIP1167_PREFIX)
+            && code.ends_with(&EIP1167_SUFFIX)
+        {
+            AddressKind::Clone
+        } else if code
+            .windows(SAFE_MASTER_COPY.len() + 28)
+            .any(|w| w.starts_with(&SAFE_MASTER_COPY) && w[SAFE_MASTER_COPY.len()..].iter().all(|b| *b == 0))
+        {
+            AddressKind::Safe
+        } else {
+            AddressKind::Contract
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AddressKind::Eoa => "eoa",
+            AddressKind::Safe => "safe",
+            AddressKind::Clone => "clone",
+            AddressKind::Contract => "contract",
+        }
+    }
+}
+
+impl fmt::Display for AddressKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A classification and when it was made
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Cached {
+    kind: AddressKind,
+    /// Unix seconds
+    checked: u64,
+}
+
+/// Classifies unlabeled addresses with `eth_getCode`, caching the results on disk
+///
+/// Contracts are cached for good; EOAs are rechecked after
+/// [`EOA_RECHECK_SECS`]. Shared by all chain monitors. New entries are
+/// written out in batches, [`SAVE_DELAY`] after the first one.
+#[derive(Debug, Default)]
+pub struct AddressClassifier {
+    enabled: bool,
+    path: Option<PathBuf>,
+    cache: Arc<Mutex<BTreeMap<String, Cached>>>,
+    save_pending: Arc<AtomicBool>,
+}
+
+impl AddressClassifier {
+    /// A classifier that never looks anything up
+    pub fn disabled() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Load the cache file from the config, or the default one in the user's cache directory
+    pub fn open(config: &ClassifyConfig) -> Result<Arc<Self>> {
+        if !config.enabled {
+            return Ok(Self::disabled());
+        }
+        let path = config.cache_path.clone().or_else(default_cache_path);
+        let cache = match &path {
+            Some(path) if path.exists() => serde_json::from_str(&fs::read_to_string(path)?)
+                .map_err(|e| eyre::eyre!("{}: {}", path.display(), e))?,
+            _ => BTreeMap::new(),
+        };
+
+        Ok(Arc::new(Self {
+            enabled: true,
+            path,
+            cache: Arc::new(Mutex::new(cache)),
+            save_pending: Arc::new(AtomicBool::new(false)),
+        }))
+    }
+
+    /// Number of cached classifications
+    pub fn len(&self) -> usize {
+        self.cache.lock().expect("classifier lock poisoned").len()
+    }
+
+    /// Classify an address, fetching its code when the cache has no fresh answer
+    ///
+    /// Returns `None` when disabled or when the lookup fails.
+    pub async fn classify<T, P>(&self, provider: &P, chain: Chain, address: Address) -> Option<AddressKind>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        if !self.enabled {
+            return None;
+        }
+        let key = cache_key(chain, &address);
+        let now = now_secs();
+        if let Some(cached) = self.cache.lock().expect("classifier lock poisoned").get(&key) {
+            if cached.kind != AddressKind::Eoa || now.saturating_sub(cached.checked) < EOA_RECHECK_SECS {
+                return Some(cached.kind);
+            }
+        }
+
+        let code = match provider.get_code_at(address).await {
+            Ok(code) => code,
+            Err(e) => {
+                tracing::debug!(%chain, %address, error = %e, "Failed to fetch code");
+                return None;
+            }
+        };
+        let kind = AddressKind::from_code(&code);
+        self.insert(key, Cached { kind, checked: now });
+        Some(kind)
+    }
+
+    /// Write out pending classifications now
+    pub async fn flush(&self) {
+        if let Some(path) = &self.path {
+            self.save_pending.store(false, Ordering::Relaxed);
+            save_in_background(path.clone(), &self.cache).await;
+        }
+    }
+
+    fn insert(&self, key: String, cached: Cached) {
+        self.cache.lock().expect("classifier lock poisoned").insert(key, cached);
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        if self.save_pending.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        let cache = Arc::clone(&self.cache);
+        let save_pending = Arc::clone(&self.save_pending);
+        tokio::spawn(async move {
+            tokio::time::sleep(SAVE_DELAY).await;
+            // Entries added from here on schedule the next save
+            save_pending.store(false, Ordering::Relaxed);
+            save_in_background(path, &cache).await;
+        });
+    }
+}
+
+/// Prune and copy the cache under the lock, then write it off the async threads
+async fn save_in_background(path: PathBuf, cache: &Mutex<BTreeMap<String, Cached>>) {
+    let snapshot = {
+        let mut cache = cache.lock().expect("classifier lock poisoned");
+        prune(&mut cache, now_secs());
+        cache.clone()
+    };
+    let result = tokio::task::spawn_blocking({
+        let path = path.clone();
+        move || save(&path, &snapshot)
+    })
+    .await
+    .map_err(eyre::Report::from)
+    .and_then(|result| result);
+    if let Err(e) = result {
+        tracing::warn!(path = %path.display(), error = %e, "Failed to save address kinds");
+    }
+}
+
+/// Drop EOAs due for a recheck anyway, then the oldest entries beyond [`MAX_ENTRIES`]
+fn prune(cache: &mut BTreeMap<String, Cached>, now: u64) {
+    cache.retain(|_, cached| {
+        cached.kind != AddressKind::Eoa || now.saturating_sub(cached.checked) < EOA_RECHECK_SECS
+    });
+    if cache.len() > MAX_ENTRIES {
+        let mut by_age: Vec<(u64, String)> =
+            cache.iter().map(|(key, cached)| (cached.checked, key.clone())).collect();
+        by_age.sort_unstable();
+        for (_, key) in by_age.into_iter().take(cache.len() - MAX_ENTRIES) {
+            cache.remove(&key);
+        }
+    }
+}
+
+/// Write the cache through a temporary file so a crash never leaves it truncated
+fn save(path: &Path, cache: &BTreeMap<String, Cached>) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_vec(cache)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn cache_key(chain: Chain, address: &Address) -> String {
+    format!("{}:{}", chain.name().to_lowercase(), address.to_checksum(None))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// `$XDG_CACHE_HOME/usdc_whale_detector/address_kinds.json`, falling back to `~/.cache`
+fn default_cache_path() -> Option<PathBuf> {
+    let cache_dir = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
+    Some(cache_dir.join("usdc_whale_detector").join("address_kinds.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runtime code of the Safe v1.3.0 proxy
+    const SAFE_PROXY: &str = "608060405273ffffffffffffffffffffffffffffffffffffffff600054167fa619486e0000000000000000000000000000000000000000000000000000000060003514156050578060005260206000f35b3660008037600080366000845af43d6000803e60008114156070573d6000fd5b3d6000f3fea2646970667358221220d1429297349653a4918076d650332de1a1068c5f3e07c5c82360c277770b955264736f6c63430007060033";
+
+    #[test]
+    fn test_classifies_code() {
+        assert_eq!(AddressKind::from_code(&[]), AddressKind::Eoa);
+        let delegated = [&EIP7702_PREFIX[..], &[0x11; 20]].concat();
+        assert_eq!(AddressKind::from_code(&delegated), AddressKind::Eoa);
+
+        let clone = [&EIP1167_PREFIX[..], &[0x22; 20], &EIP1167_SUFFIX[..]].concat();
+        assert_eq!(AddressKind::from_code(&clone), AddressKind::Clone);
+
+        assert_eq!(AddressKind::from_code(&hex::decode(SAFE_PROXY).unwrap()), AddressKind::Safe);
+        assert_eq!(AddressKind::from_code(&hex!("6080604052")), AddressKind::Contract);
+    }
+
+    #[test]
+    fn test_prunes_stale_eoas() {
+        let now = EOA_RECHECK_SECS * 2;
+        let mut cache = BTreeMap::from([
+            ("stale".to_string(), Cached { kind: AddressKind::Eoa, checked: 1 }),
+            ("fresh".to_string(), Cached { kind: AddressKind::Eoa, checked: now }),
+            ("old_contract".to_string(), Cached { kind: AddressKind::Contract, checked: 1 }),
+        ]);
+        prune(&mut cache, now);
+        assert_eq!(cache.keys().collect::<Vec<_>>(), ["fresh", "old_contract"]);
+    }
+
+    #[tokio::test]
+    async fn test_persists_cache() {
+        use alloy::providers::ProviderBuilder;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": format!("0x{}", SAFE_PROXY),
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let provider = ProviderBuilder::new().on_http(server.uri().parse().unwrap());
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = ClassifyConfig {
+            enabled: true,
+            cache_path: Some(dir.path().join("kinds.json")),
+        };
+        let address = Address::repeat_byte(7);
+        let classifier = AddressClassifier::open(&config).unwrap();
+        assert_eq!(
+            classifier.classify(&provider, Chain::Base, address).await,
+            Some(AddressKind::Safe)
+        );
+        // Served from the cache, so the mock sees a single request
+        assert_eq!(
+            classifier.classify(&provider, Chain::Base, address).await,
+            Some(AddressKind::Safe)
+        );
+
+        // Writes are batched, so nothing is on disk until the delay or a flush
+        assert!(!dir.path().join("kinds.json").exists());
+        classifier.flush().await;
+        let reopened = AddressClassifier::open(&config).unwrap();
+        assert_eq!(reopened.len(), 1);
+        assert_eq!(
+            reopened.classify(&provider, Chain::Base, address).await,
+            Some(AddressKind::Safe)
+        );
+        assert_eq!(AddressClassifier::disabled().classify(&provider, Chain::Base, address).await, None);
+    }
+}
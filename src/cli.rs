@@ -0,0 +1,365 @@
+use crate::i18n::Lang;
+use crate::labels::{LabelCategory, LabelFormat};
+use crate::store::SortKey;
+use crate::types::Chain;
+
+use alloy::primitives::Address;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+/// Command line arguments
+#[derive(Debug, Parser)]
+#[command(name = "usdc_whale_detector", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Path to the TOML config file (defaults to ./config.toml if present)
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// How detected transfers are written
+    #[arg(long, value_enum, default_value_t = OutputMode::Pretty)]
+    pub output: OutputMode,
+
+    /// When to color console output
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
+    /// Output language (defaults to `language` from the config, then the locale)
+    #[arg(long, value_enum)]
+    pub lang: Option<Lang>,
+
+    /// Write JSON Lines to this file instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub output_file: Option<PathBuf>,
+
+    /// Rotate the output file once it grows past this many megabytes
+    #[arg(long, value_name = "MB", default_value_t = 100)]
+    pub rotate_size_mb: u64,
+
+    /// Number of rotated output files to keep
+    #[arg(long, value_name = "N", default_value_t = 5)]
+    pub rotate_keep: usize,
+}
+
+/// Output mode for detected transfers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputMode {
+    /// Colored multi-line block per transfer
+    Pretty,
+    /// One line per transfer
+    Compact,
+    /// Aligned table columns
+    Table,
+    /// Uncolored block without emoji
+    Plain,
+    /// One JSON object per line (see `schema.rs`)
+    Jsonl,
+    /// Full-screen dashboard (requires the `tui` feature)
+    Tui,
+}
+
+/// Whether console output is colored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Only when stdout is a terminal and `NO_COLOR` is unset
+    Auto,
+    Always,
+    Never,
+}
+
+/// Subcommands; without one the monitor runs
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Search the stored transfer history
+    Query(QueryArgs),
+    /// Write transfer history to CSV or Parquet files partitioned by chain and day
+    Export(ExportArgs),
+    /// Manage address labels
+    Labels(LabelsArgs),
+}
+
+/// Arguments for `query`
+#[derive(Debug, Args)]
+pub struct QueryArgs {
+    /// History database (defaults to `history.sqlite_path` from the config)
+    #[arg(long, value_name = "PATH")]
+    pub db: Option<PathBuf>,
+
+    /// Only these chains (repeat or comma-separate)
+    #[arg(long, value_delimiter = ',')]
+    pub chain: Vec<Chain>,
+
+    /// Transfers sent from or to this address
+    #[arg(long)]
+    pub address: Option<Address>,
+
+    /// Transfers whose from or to label contains this text (case-insensitive)
+    #[arg(long)]
+    pub label: Option<String>,
+
+    /// Minimum amount in USD
+    #[arg(long, value_name = "USD")]
+    pub min_amount: Option<f64>,
+
+    /// Maximum amount in USD
+    #[arg(long, value_name = "USD")]
+    pub max_amount: Option<f64>,
+
+    /// Start time: RFC 3339, YYYY-MM-DD, or relative like 7d, 12h, 30m
+    #[arg(long, value_parser = parse_time)]
+    pub since: Option<DateTime<Utc>>,
+
+    /// End time (exclusive), same formats as --since
+    #[arg(long, value_parser = parse_time)]
+    pub until: Option<DateTime<Utc>>,
+
+    /// Sort order
+    #[arg(long, value_enum, default_value_t = SortKey::Time)]
+    pub sort: SortKey,
+
+    /// Sort ascending instead of descending
+    #[arg(long)]
+    pub asc: bool,
+
+    /// Maximum number of transfers to show
+    #[arg(long, default_value_t = 20)]
+    pub limit: usize,
+
+    /// Show transfer count and volume per day and chain instead of transfers
+    #[arg(long)]
+    pub daily: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = QueryFormat::Table)]
+    pub format: QueryFormat,
+}
+
+/// Arguments for `export`
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// History database (defaults to `history.sqlite_path` from the config)
+    #[arg(long, value_name = "PATH", conflicts_with = "input")]
+    pub db: Option<PathBuf>,
+
+    /// Read transfers from a JSON Lines file instead of the database
+    #[arg(long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+
+    /// Output directory; files go to `<dir>/chain=<chain>/date=<YYYY-MM-DD>/`
+    #[arg(long, value_name = "DIR")]
+    pub out_dir: PathBuf,
+
+    /// File format
+    #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+    pub format: ExportFormat,
+
+    /// Only these chains (repeat or comma-separate)
+    #[arg(long, value_delimiter = ',')]
+    pub chain: Vec<Chain>,
+
+    /// Start time, same formats as `query --since`
+    #[arg(long, value_parser = parse_time)]
+    pub since: Option<DateTime<Utc>>,
+
+    /// End time (exclusive)
+    #[arg(long, value_parser = parse_time)]
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Arguments for `labels`
+#[derive(Debug, Args)]
+pub struct LabelsArgs {
+    #[command(subcommand)]
+    pub command: LabelsCommand,
+}
+
+/// Subcommands of `labels`
+#[derive(Debug, Subcommand)]
+pub enum LabelsCommand {
+    /// Merge CSV, JSON, eth-labels or etherscan-labels files into a label file
+    Import(LabelsImportArgs),
+    /// Write the merged labels from every configured source
+    Export(LabelsExportArgs),
+    /// Add or replace a label in a label file
+    Add(LabelsAddArgs),
+    /// Remove a label from a label file
+    Remove(LabelsRemoveArgs),
+    /// Find labels by name, entity or address across every configured source
+    Search(LabelsSearchArgs),
+    /// Check label files for invalid entries, duplicates and unchecksummed addresses
+    Lint(LabelsLintArgs),
+}
+
+/// Arguments for `labels import`
+#[derive(Debug, Args)]
+pub struct LabelsImportArgs {
+    /// Files to import, later files overriding earlier ones
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// Input format (defaults to the file extension)
+    #[arg(long, value_enum)]
+    pub format: Option<LabelFormat>,
+
+    /// Label file to merge into; created if missing
+    #[arg(long, value_name = "PATH", default_value = "data/labels.json")]
+    pub into: PathBuf,
+
+    /// Scope labels without a chain to this chain
+    #[arg(long)]
+    pub chain: Option<Chain>,
+
+    /// Source recorded on labels that don't name one
+    #[arg(long)]
+    pub source: Option<String>,
+}
+
+/// Arguments for `labels export`
+#[derive(Debug, Args)]
+pub struct LabelsExportArgs {
+    /// Output format (defaults to the output extension, then JSON)
+    #[arg(long, value_enum)]
+    pub format: Option<LabelFormat>,
+
+    /// Output file (defaults to stdout)
+    #[arg(long, short, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+/// Arguments for `labels add`
+#[derive(Debug, Args)]
+pub struct LabelsAddArgs {
+    pub address: Address,
+
+    pub label: String,
+
+    /// Only apply the label on this chain
+    #[arg(long)]
+    pub chain: Option<Chain>,
+
+    /// Organization behind the address
+    #[arg(long)]
+    pub entity: Option<String>,
+
+    /// cex, defi, bridge, market_maker, issuer or fund
+    #[arg(long)]
+    pub category: Option<LabelCategory>,
+
+    /// Where the label came from
+    #[arg(long)]
+    pub source: Option<String>,
+
+    /// Confidence between 0 and 1
+    #[arg(long)]
+    pub confidence: Option<f64>,
+
+    /// Label file to edit; created if missing
+    #[arg(long, value_name = "PATH", default_value = "data/labels.json")]
+    pub file: PathBuf,
+
+    /// Replace an existing label for the address
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Arguments for `labels remove`
+#[derive(Debug, Args)]
+pub struct LabelsRemoveArgs {
+    pub address: Address,
+
+    /// Remove the label scoped to this chain instead of the global one
+    #[arg(long)]
+    pub chain: Option<Chain>,
+
+    /// Label file to edit
+    #[arg(long, value_name = "PATH", default_value = "data/labels.json")]
+    pub file: PathBuf,
+}
+
+/// Arguments for `labels search`
+#[derive(Debug, Args)]
+pub struct LabelsSearchArgs {
+    /// Text in the label or entity (case-insensitive), or an address or address prefix
+    pub query: String,
+
+    /// Only labels that apply on this chain
+    #[arg(long)]
+    pub chain: Option<Chain>,
+
+    /// Only labels in this category
+    #[arg(long)]
+    pub category: Option<LabelCategory>,
+}
+
+/// Arguments for `labels lint`
+#[derive(Debug, Args)]
+pub struct LabelsLintArgs {
+    /// Files to check (defaults to every configured label file)
+    pub files: Vec<PathBuf>,
+
+    /// Rewrite files with checksummed addresses, sorted and without duplicates
+    #[arg(long)]
+    pub fix: bool,
+}
+
+/// File format for `export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// CSV with a header row
+    Csv,
+    /// Apache Parquet (requires the `parquet` feature)
+    Parquet,
+}
+
+/// Output format for `query`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum QueryFormat {
+    /// Aligned columns
+    Table,
+    /// Comma-separated values with a header row
+    Csv,
+    /// JSON array
+    Json,
+}
+
+/// Parse an absolute (RFC 3339 or YYYY-MM-DD) or relative (7d, 12h, 30m) time
+pub fn parse_time(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+
+    let invalid = || format!("invalid time: {}", value);
+    let (index, unit) = value.char_indices().next_back().ok_or_else(invalid)?;
+    let number: i64 = value[..index].parse().map_err(|_| invalid())?;
+    let ago = match unit {
+        'd' => Duration::try_days(number),
+        'h' => Duration::try_hours(number),
+        'm' => Duration::try_minutes(number),
+        'w' => Duration::try_weeks(number),
+        _ => None,
+    };
+    ago.and_then(|ago| Utc::now().checked_sub_signed(ago))
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("2024-12-08").unwrap().to_rfc3339(), "2024-12-08T00:00:00+00:00");
+        let week_ago = parse_time("1w").unwrap();
+        assert!((Utc::now() - week_ago - Duration::weeks(1)).num_seconds().abs() < 5);
+
+        for value in ["", "d", "7", "7x", "7é", "é", "9999999999999999d"] {
+            assert!(parse_time(value).is_err(), "{}", value);
+        }
+    }
+}
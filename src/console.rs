@@ -0,0 +1,298 @@
+use crate::cli::{ColorChoice, OutputMode};
+use crate::i18n::{self, char_width, display_width};
+use crate::types::{Chain, WhaleTransfer};
+
+use chrono::Local;
+use colored::{ColoredString, Colorize};
+use std::io::IsTerminal;
+
+/// Rows printed between repeated table headers
+const TABLE_HEADER_EVERY: usize = 25;
+
+/// Column widths of the table format; time and amount depend on the language
+fn table_widths() -> [usize; 6] {
+    let messages = i18n::messages();
+    [
+        display_width(&messages.datetime(&Local::now())),
+        8,
+        display_width(&messages.amount(9_999_999_999.99)),
+        36,
+        36,
+        21,
+    ]
+}
+
+/// Decide once whether console output is colored
+///
+/// `auto` disables colors when stdout is not a terminal or `NO_COLOR` is set;
+/// the plain format is never colored.
+pub fn init_colors(choice: ColorChoice, mode: OutputMode) {
+    let enabled = match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            std::io::stdout().is_terminal()
+                && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+        }
+    };
+    colored::control::set_override(enabled && mode != OutputMode::Plain);
+}
+
+/// Held by tests that change the global color override
+#[cfg(test)]
+pub(crate) static COLOR_OVERRIDE: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Prints detected transfers in one of the human-readable formats
+pub struct Console {
+    mode: OutputMode,
+    printed: usize,
+}
+
+impl Console {
+    /// Create a console printer for the given output mode
+    pub fn new(mode: OutputMode) -> Self {
+        Self { mode, printed: 0 }
+    }
+
+    /// Print one transfer
+    pub fn print(&mut self, transfer: &WhaleTransfer) {
+        match self.mode {
+            OutputMode::Compact => println!("{}", compact(transfer)),
+            OutputMode::Table => {
+                if self.printed.is_multiple_of(TABLE_HEADER_EVERY) {
+                    println!("{}", table_header());
+                }
+                println!("{}", table_row(transfer));
+            }
+            OutputMode::Plain => println!("{}", plain(transfer)),
+            _ => println!("{}", pretty(transfer)),
+        }
+        self.printed += 1;
+    }
+}
+
+fn chain_color(chain: Chain) -> &'static str {
+    match chain {
+        Chain::Ethereum => "blue",
+        Chain::Arbitrum => "cyan",
+        Chain::Base => "magenta",
+    }
+}
+
+fn chain_label(text: &str, chain: Chain) -> ColoredString {
+    text.color(chain_color(chain)).bold()
+}
+
+fn timestamp() -> String {
+    i18n::messages().datetime(&Local::now())
+}
+
+/// Field labels of the block formats padded to the same width, e.g. `From:  `
+fn field_labels() -> [String; 6] {
+    let messages = i18n::messages();
+    let names = [
+        messages.amount,
+        messages.from,
+        messages.to,
+        messages.tx,
+        messages.block,
+        messages.link,
+    ];
+    let width = names.iter().map(|name| display_width(name)).max().unwrap_or(0) + 1;
+    names.map(|name| fit(&format!("{}:", name), width))
+}
+
+/// Colored multi-line block
+fn pretty(transfer: &WhaleTransfer) -> String {
+    let [amount, from, to, tx, block, link] = field_labels();
+    let lines = [
+        String::new(),
+        format!(
+            "{} {} 🐋 {}",
+            format!("[{}]", timestamp()).bright_black(),
+            chain_label(&format!("[{}]", transfer.chain.name()), transfer.chain),
+            i18n::messages().whale_detected.bright_yellow().bold()
+        ),
+        format!(
+            "  {} {}",
+            amount.bright_white(),
+            transfer.amount_text().bright_green().bold()
+        ),
+        format!("  {} {}", from.bright_white(), transfer.formatted_from()),
+        format!("  {} {}", to.bright_white(), transfer.formatted_to()),
+        format!(
+            "  {} {}",
+            tx.bright_white(),
+            transfer.short_tx_hash().bright_blue()
+        ),
+        format!(
+            "  {} {}",
+            block.bright_white(),
+            transfer.block_number.to_string().bright_black()
+        ),
+        format!(
+            "  {} {}",
+            link.bright_white(),
+            transfer
+                .chain
+                .explorer_tx_url(&transfer.tx_hash)
+                .bright_blue()
+                .underline()
+        ),
+    ];
+    lines.join("\n")
+}
+
+/// Single line per transfer
+fn compact(transfer: &WhaleTransfer) -> String {
+    format!(
+        "{} {} {} {} → {} {}",
+        timestamp().bright_black(),
+        chain_label(transfer.chain.name(), transfer.chain),
+        transfer.amount_text().bright_green().bold(),
+        transfer.formatted_from(),
+        transfer.formatted_to(),
+        transfer.short_tx_hash().bright_blue()
+    )
+}
+
+/// Pad or cut text to exactly `width` terminal columns
+pub fn fit(text: &str, width: usize) -> String {
+    let mut fitted = String::new();
+    let mut used = 0;
+
+    if display_width(text) > width {
+        for c in text.chars() {
+            if used + char_width(c) + 1 > width {
+                break;
+            }
+            fitted.push(c);
+            used += char_width(c);
+        }
+        fitted.push('…');
+        used += 1;
+    } else {
+        fitted.push_str(text);
+        used = display_width(text);
+    }
+
+    fitted.push_str(&" ".repeat(width.saturating_sub(used)));
+    fitted
+}
+
+/// Right-align text in `width` terminal columns
+fn fit_right(text: &str, width: usize) -> String {
+    format!("{}{}", " ".repeat(width.saturating_sub(display_width(text))), text)
+}
+
+fn table_header() -> String {
+    let messages = i18n::messages();
+    let [time, chain, amount, from, to, tx] = table_widths();
+    format!(
+        "{} {} {} {} {} {}",
+        fit(&messages.time.to_uppercase(), time),
+        fit(&messages.chain.to_uppercase(), chain),
+        fit_right(&messages.amount.to_uppercase(), amount),
+        fit(&messages.from.to_uppercase(), from),
+        fit(&messages.to.to_uppercase(), to),
+        fit(&messages.tx.to_uppercase(), tx),
+    )
+    .bold()
+    .to_string()
+}
+
+/// Aligned table row; colors are applied after padding so ANSI codes don't shift columns
+fn table_row(transfer: &WhaleTransfer) -> String {
+    let [time, chain, amount, from, to, tx] = table_widths();
+    let row = format!(
+        "{} {} {} {} {} {}",
+        fit(&timestamp(), time).bright_black(),
+        chain_label(&fit(transfer.chain.name(), chain), transfer.chain),
+        fit_right(&transfer.formatted_amount(), amount).bright_green(),
+        fit(&transfer.formatted_from(), from),
+        fit(&transfer.formatted_to(), to),
+        fit(&transfer.short_tx_hash(), tx).bright_blue(),
+    );
+    match transfer.formatted_fiat() {
+        Some(fiat) => format!("{} {}", row, fiat.bright_black()),
+        None => row,
+    }
+}
+
+/// Uncolored block without emoji, for logs and screen readers
+fn plain(transfer: &WhaleTransfer) -> String {
+    let messages = i18n::messages();
+    let [amount, from, to, _, block, link] = field_labels();
+    format!(
+        "{} {}\n  {} {}\n  {} {}\n  {} {}\n  {} {}\n  {} {}\n",
+        timestamp(),
+        i18n::fill(messages.whale_transfer_on, &[&transfer.chain.name()]),
+        amount,
+        transfer.amount_text(),
+        from,
+        transfer.formatted_from(),
+        to,
+        transfer.formatted_to(),
+        block,
+        transfer.block_number,
+        link,
+        transfer.chain.explorer_tx_url(&transfer.tx_hash),
+    )
+}
+
+/// Print startup banner
+pub fn print_banner() {
+    let messages = i18n::messages();
+    let border = "═".repeat(63);
+    let line = |text: &str| format!("║{}║", fit(text, 63)).bright_cyan();
+
+    println!();
+    println!("{}", format!("╔{}╗", border).bright_cyan());
+    println!("{}", line(""));
+    println!("{}", line(&format!("   🐋  {}  🐋", messages.banner_title)));
+    println!("{}", line(""));
+    println!("{}", line(&format!("   {}", messages.banner_subtitle)));
+    println!("{}", line(""));
+    println!("{}", format!("╚{}╝", border).bright_cyan());
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Address, B256, U256};
+
+    fn transfer() -> WhaleTransfer {
+        WhaleTransfer::new(
+            Chain::Base,
+            B256::repeat_byte(1),
+            123,
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            U256::from(2_500_000_000_000u64),
+        )
+        .with_from_label(Some("A very long exchange hot wallet label".to_string()))
+    }
+
+    #[test]
+    fn test_formats() {
+        let _colors = COLOR_OVERRIDE.lock().unwrap();
+        colored::control::set_override(false);
+        let transfer = transfer();
+
+        let line = compact(&transfer);
+        assert_eq!(line.lines().count(), 1);
+        assert!(line.contains("BASE $2,500,000.00 USDC 0x02020202...02020202 (A very long"));
+
+        let header = table_header();
+        let row = table_row(&transfer);
+        assert_eq!(display_width(&header), display_width(&row));
+        assert_eq!(header.find("FROM"), row.find("0x0202"));
+        assert!(row.contains("…"));
+        assert_eq!(display_width(&fit("고래 이체", 6)), 6);
+
+        let block = plain(&transfer);
+        assert!(block.contains("  Amount: $2,500,000.00 USDC\n"));
+        assert!(block.is_ascii());
+    }
+}
@@ -0,0 +1,348 @@
+use crate::config::EnsConfig;
+use crate::types::Chain;
+
+use alloy::primitives::{address, hex, keccak256, Address, B256};
+use alloy::providers::Provider;
+use alloy::sol;
+use alloy::transports::Transport;
+use eyre::Result;
+use idna::uts46::{AsciiDenyList, Hyphens, Uts46};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// ENS registry on Ethereum mainnet
+pub const ENS_REGISTRY: Address = address!("00000000000C2E074eC69A0dFb2997BA6C7d2e1e");
+
+sol! {
+    #[sol(rpc)]
+    interface EnsRegistry {
+        function resolver(bytes32 node) external view returns (address);
+    }
+
+    #[sol(rpc)]
+    interface EnsResolver {
+        function name(bytes32 node) external view returns (string);
+        function addr(bytes32 node) external view returns (address);
+    }
+}
+
+/// Resolves ENS primary names of unlabeled Ethereum addresses
+///
+/// A reverse record is only trusted when the name resolves back to the same
+/// address, since anyone can set any name as their reverse record. Names and
+/// misses are cached for `cache_secs`; failed lookups are not cached.
+#[derive(Debug, Default)]
+pub struct EnsNames {
+    enabled: bool,
+    registry: Address,
+    ttl: Duration,
+    cache: Mutex<HashMap<Address, (Option<String>, Instant)>>,
+}
+
+impl EnsNames {
+    /// Names that are never looked up
+    pub fn disabled() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn new(config: &EnsConfig) -> Arc<Self> {
+        if !config.enabled {
+            return Self::disabled();
+        }
+        Arc::new(Self {
+            enabled: true,
+            registry: config.registry.unwrap_or(ENS_REGISTRY),
+            ttl: Duration::from_secs(config.cache_secs),
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Verified primary name of an address, if it has one
+    ///
+    /// Only Ethereum is looked up; ENS names on L2s resolve through mainnet
+    /// and are not checked here.
+    pub async fn lookup<T, P>(&self, provider: &P, chain: Chain, address: Address) -> Option<String>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        if !self.enabled || chain != Chain::Ethereum {
+            return None;
+        }
+        if let Some((name, at)) = self.cache.lock().expect("ens lock poisoned").get(&address) {
+            if at.elapsed() < self.ttl {
+                return name.clone();
+            }
+        }
+
+        match self.reverse_resolve(provider, address).await {
+            Ok(name) => {
+                self.cache
+                    .lock()
+                    .expect("ens lock poisoned")
+                    .insert(address, (name.clone(), Instant::now()));
+                name
+            }
+            Err(e) => {
+                tracing::debug!(%address, error = %e, "ENS lookup failed");
+                None
+            }
+        }
+    }
+
+    async fn reverse_resolve<T, P>(&self, provider: &P, address: Address) -> Result<Option<String>>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let registry = EnsRegistry::new(self.registry, provider);
+        let reverse_node = reverse_node(&address);
+        let resolver = registry.resolver(reverse_node).call().await?._0;
+        if resolver.is_zero() {
+            return Ok(None);
+        }
+        let name = EnsResolver::new(resolver, provider)
+            .name(reverse_node)
+            .call()
+            .await?
+            ._0;
+        // The name is set by the address owner, so only accept clean, normalized names
+        let Some(normalized) = normalize(&name) else {
+            tracing::debug!(%address, ?name, "Ignoring invalid ENS name");
+            return Ok(None);
+        };
+        if normalized != name {
+            tracing::debug!(%address, ?name, "Ignoring unnormalized ENS name");
+            return Ok(None);
+        }
+
+        // Forward check: the normalized name must point back at the address
+        let node = namehash(&normalized);
+        let resolver = registry.resolver(node).call().await?._0;
+        if resolver.is_zero() {
+            return Ok(None);
+        }
+        let resolved = EnsResolver::new(resolver, provider).addr(node).call().await?._0;
+        Ok((resolved == address).then_some(normalized))
+    }
+}
+
+/// UTS-46 normalized form of an ENS name, or `None` if it is not a valid name
+///
+/// Rejects control characters outright so they can never reach a terminal.
+pub fn normalize(name: &str) -> Option<String> {
+    if name.is_empty() || name.chars().any(char::is_control) {
+        return None;
+    }
+    let (normalized, result) =
+        Uts46::new().to_unicode(name.as_bytes(), AsciiDenyList::URL, Hyphens::Allow);
+    if result.is_err() || normalized.split('.').any(str::is_empty) {
+        return None;
+    }
+    Some(normalized.into_owned())
+}
+
+/// ENS namehash of a dot-separated name
+pub fn namehash(name: &str) -> B256 {
+    if name.is_empty() {
+        return B256::ZERO;
+    }
+    name.rsplit('.').fold(B256::ZERO, |node, label| {
+        keccak256([node.as_slice(), keccak256(label.as_bytes()).as_slice()].concat())
+    })
+}
+
+/// Node of `<address>.addr.reverse`, where the primary name is set
+fn reverse_node(address: &Address) -> B256 {
+    namehash(&format!("{}.addr.reverse", hex::encode(address)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::node_bindings::Anvil;
+    use alloy::primitives::Bytes;
+    use alloy::providers::ext::AnvilApi;
+    use alloy::providers::ProviderBuilder;
+    use alloy::sol_types::SolCall;
+    use serde_json::{json, Value};
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    /// JSON-RPC endpoint answering `eth_call` like [`mock_ens`]
+    struct MockRpc {
+        owner: Address,
+        name: &'static str,
+    }
+
+    impl Respond for MockRpc {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let body: Value = serde_json::from_slice(&request.body).unwrap();
+            let call = &body["params"][0];
+            let input = call["input"].as_str().or(call["data"].as_str()).unwrap();
+            let input = hex::decode(input).unwrap();
+            let result = match <[u8; 4]>::try_from(&input[..4]).unwrap() {
+                EnsRegistry::resolverCall::SELECTOR => {
+                    B256::left_padding_from(&hex::decode(call["to"].as_str().unwrap()).unwrap()).to_vec()
+                }
+                EnsResolver::addrCall::SELECTOR => B256::left_padding_from(self.owner.as_slice()).to_vec(),
+                _ => [
+                    B256::with_last_byte(0x20),
+                    B256::with_last_byte(self.name.len() as u8),
+                    B256::right_padding_from(self.name.as_bytes()),
+                ]
+                .concat(),
+            };
+            ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": body["id"],
+                "result": hex::encode_prefixed(result),
+            }))
+        }
+    }
+
+    /// Runtime code answering as both ENS registry and resolver
+    ///
+    /// `resolver()` returns the contract itself, `name()` returns `name` and
+    /// `addr()` returns `owner`, whatever node is asked for.
+    fn mock_ens(owner: Address, name: &str) -> Bytes {
+        assert!(name.len() <= 32);
+        let mut code = hex!("60003560e01c").to_vec();
+        // Jump targets are patched in once the code layout is known
+        let mut jumps = Vec::new();
+        for selector in [
+            EnsRegistry::resolverCall::SELECTOR,
+            EnsResolver::nameCall::SELECTOR,
+            EnsResolver::addrCall::SELECTOR,
+        ] {
+            code.extend([0x80, 0x63]);
+            code.extend(selector);
+            code.extend([0x14, 0x60, 0x00, 0x57]);
+            jumps.push(code.len() - 2);
+        }
+        code.extend(hex!("60006000fd"));
+
+        let mut targets = Vec::new();
+        // resolver(): the contract's own address
+        targets.push(code.len());
+        code.extend(hex!("5b3060005260206000f3"));
+        // name(): ABI-encoded string copied from the end of the code
+        targets.push(code.len());
+        code.extend(hex!("5b6060"));
+        let data_offset = code.len() + 1;
+        code.extend([0x60, 0x00]);
+        code.extend(hex!("60003960606000f3"));
+        // addr(): the owner
+        targets.push(code.len());
+        code.extend([0x5b, 0x73]);
+        code.extend(owner);
+        code.extend(hex!("60005260206000f3"));
+
+        let data_start = code.len();
+        code[data_offset] = data_start as u8;
+        for (jump, target) in jumps.into_iter().zip(targets) {
+            code[jump] = target as u8;
+        }
+        code.extend(B256::with_last_byte(0x20));
+        code.extend(B256::with_last_byte(name.len() as u8));
+        let mut padded = [0u8; 32];
+        padded[..name.len()].copy_from_slice(name.as_bytes());
+        code.extend(padded);
+        code.into()
+    }
+
+    #[test]
+    fn test_namehash() {
+        assert_eq!(namehash(""), B256::ZERO);
+        assert_eq!(
+            namehash("eth"),
+            B256::from(hex!("93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae"))
+        );
+        assert_eq!(
+            namehash("foo.eth"),
+            B256::from(hex!("de9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f"))
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("vitalik.eth").as_deref(), Some("vitalik.eth"));
+        assert_eq!(normalize("Vitalik.ETH").as_deref(), Some("vitalik.eth"));
+        assert_eq!(normalize("whale\u{1b}[31m.eth"), None);
+        assert_eq!(normalize("evil\u{202e}.eth"), None);
+        assert_eq!(normalize("a b.eth"), None);
+        assert_eq!(normalize("foo..eth"), None);
+        assert_eq!(normalize(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unnormalized_names() {
+        let server = MockServer::start().await;
+        let owner = Address::repeat_byte(0x11);
+        Mock::given(method("POST"))
+            .respond_with(MockRpc { owner, name: "Whale.eth" })
+            .mount(&server)
+            .await;
+        let provider = ProviderBuilder::new().on_http(server.uri().parse().unwrap());
+
+        let ens = EnsNames::new(&EnsConfig::default());
+        assert_eq!(ens.lookup(&provider, Chain::Ethereum, owner).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_verifies_forward_record() {
+        let server = MockServer::start().await;
+        let owner = Address::repeat_byte(0x11);
+        Mock::given(method("POST"))
+            .respond_with(MockRpc { owner, name: "whale.eth" })
+            .mount(&server)
+            .await;
+        let provider = ProviderBuilder::new().on_http(server.uri().parse().unwrap());
+
+        let ens = EnsNames::new(&EnsConfig::default());
+        assert_eq!(
+            ens.lookup(&provider, Chain::Ethereum, owner).await.as_deref(),
+            Some("whale.eth")
+        );
+        let requests = server.received_requests().await.unwrap().len();
+        assert_eq!(requests, 4);
+        assert_eq!(
+            ens.lookup(&provider, Chain::Ethereum, Address::repeat_byte(0x22)).await,
+            None
+        );
+        // Cached lookups make no requests
+        ens.lookup(&provider, Chain::Ethereum, owner).await;
+        assert_eq!(server.received_requests().await.unwrap().len(), requests * 2);
+    }
+
+    /// Run with `cargo test ens -- --ignored` with `anvil` on the `PATH`
+    #[tokio::test]
+    #[ignore = "needs anvil"]
+    async fn test_reverse_resolution_on_anvil() {
+        let anvil = Anvil::new().spawn();
+        let provider = ProviderBuilder::new().on_http(anvil.endpoint_url());
+        let owner = anvil.addresses()[0];
+        let other = anvil.addresses()[1];
+        provider
+            .anvil_set_code(ENS_REGISTRY, mock_ens(owner, "whale.eth"))
+            .await
+            .unwrap();
+
+        let ens = EnsNames::new(&EnsConfig::default());
+        assert_eq!(
+            ens.lookup(&provider, Chain::Ethereum, owner).await.as_deref(),
+            Some("whale.eth")
+        );
+        // `other` claims the name too, but it resolves to `owner`
+        assert_eq!(ens.lookup(&provider, Chain::Ethereum, other).await, None);
+        assert_eq!(ens.lookup(&provider, Chain::Base, owner).await, None);
+
+        // Cached: later changes are not seen until the entry expires
+        provider.anvil_set_code(ENS_REGISTRY, Bytes::new()).await.unwrap();
+        assert_eq!(
+            ens.lookup(&provider, Chain::Ethereum, owner).await.as_deref(),
+            Some("whale.eth")
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use crate::cli::{ExportArgs, ExportFormat};
+use crate::config::{AppConfig, USDC_DECIMALS};
+use crate::store::{SortKey, SqliteStore, TransferQuery};
+use crate::types::{Chain, WhaleTransfer};
+
+use alloy::primitives::utils::format_units;
+use eyre::{eyre, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// Partition directory name used when the block time is unknown
+const UNKNOWN_DATE: &str = "unknown";
+
+/// Run the `export` subcommand
+pub fn run(args: ExportArgs, config: &AppConfig) -> Result<()> {
+    let transfers = match &args.input {
+        Some(input) => read_jsonl(input, &args)?,
+        None => {
+            let path = args
+                .db
+                .clone()
+                .or_else(|| config.history.sqlite_path.clone())
+                .ok_or_else(|| {
+                    eyre!("no history database: pass --db, --input or set history.sqlite_path")
+                })?;
+            if !path.exists() {
+                return Err(eyre!("history database {} does not exist", path.display()));
+            }
+            let store = SqliteStore::open(&path)?;
+            store.query(&TransferQuery {
+                chains: args.chain.clone(),
+                since: args.since,
+                until: args.until,
+                sort: SortKey::Time,
+                ascending: true,
+                ..TransferQuery::default()
+            })?
+        }
+    };
+
+    let partitions = partition(transfers);
+    for ((chain, date), transfers) in &partitions {
+        let dir = partition_dir(&args.out_dir, *chain, date);
+        fs::create_dir_all(&dir)?;
+
+        let path = match args.format {
+            ExportFormat::Csv => {
+                let path = dir.join("transfers.csv");
+                write_csv(&path, transfers)?;
+                path
+            }
+            ExportFormat::Parquet => {
+                let path = dir.join("transfers.parquet");
+                write_parquet(&path, transfers)?;
+                path
+            }
+        };
+        eprintln!("{:>6} transfers -> {}", transfers.len(), path.display());
+    }
+
+    eprintln!(
+        "Exported {} transfers in {} partitions",
+        partitions.values().map(Vec::len).sum::<usize>(),
+        partitions.len()
+    );
+    Ok(())
+}
+
+/// Read transfers from a JSON Lines file written by `--output jsonl`
+fn read_jsonl(path: &Path, args: &ExportArgs) -> Result<Vec<WhaleTransfer>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut transfers = Vec::new();
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let transfer: WhaleTransfer = serde_json::from_str(&line)
+            .map_err(|e| eyre!("{}:{}: {}", path.display(), number + 1, e))?;
+
+        let time = transfer.block_timestamp;
+        if (!args.chain.is_empty() && !args.chain.contains(&transfer.chain))
+            || args.since.is_some_and(|since| time.is_none_or(|t| t < since))
+            || args.until.is_some_and(|until| time.is_none_or(|t| t >= until))
+        {
+            continue;
+        }
+        transfers.push(transfer);
+    }
+
+    Ok(transfers)
+}
+
+/// Group transfers by chain and UTC day of the block
+fn partition(transfers: Vec<WhaleTransfer>) -> BTreeMap<(Chain, String), Vec<WhaleTransfer>> {
+    let mut partitions: BTreeMap<(Chain, String), Vec<WhaleTransfer>> = BTreeMap::new();
+    for transfer in transfers {
+        let date = transfer
+            .block_timestamp
+            .map(|t| t.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| UNKNOWN_DATE.to_string());
+        partitions
+            .entry((transfer.chain, date))
+            .or_default()
+            .push(transfer);
+    }
+    partitions
+}
+
+/// Hive-style partition directory, e.g. `out/chain=base/date=2024-12-08`
+fn partition_dir(out_dir: &Path, chain: Chain, date: &str) -> PathBuf {
+    out_dir
+        .join(format!("chain={}", chain.name().to_lowercase()))
+        .join(format!("date={}", date))
+}
+
+/// Exact decimal amount, e.g. `1500000.250000`
+fn exact_amount(transfer: &WhaleTransfer) -> String {
+    format_units(transfer.amount_raw, USDC_DECIMALS)
+        .unwrap_or_else(|_| transfer.amount_raw.to_string())
+}
+
+/// One CSV row per transfer
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    chain: String,
+    chain_id: u64,
+    block_number: u64,
+    block_timestamp: String,
+    tx_hash: String,
+    log_index: u64,
+    from: String,
+    from_label: &'a str,
+    to: String,
+    to_label: &'a str,
+    amount: String,
+    status: &'static str,
+}
+
+fn write_csv(path: &Path, transfers: &[WhaleTransfer]) -> Result<()> {
+    let mut writer = csv::Writer::from_path(path)?;
+    for transfer in transfers {
+        writer.serialize(CsvRow {
+            chain: transfer.chain.name().to_lowercase(),
+            chain_id: transfer.chain.chain_id(),
+            block_number: transfer.block_number,
+            block_timestamp: transfer
+                .block_timestamp
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+            tx_hash: format!("{:?}", transfer.tx_hash),
+            log_index: transfer.log_index,
+            from: format!("{:?}", transfer.from),
+            from_label: transfer.from_label.as_deref().unwrap_or_default(),
+            to: format!("{:?}", transfer.to),
+            to_label: transfer.to_label.as_deref().unwrap_or_default(),
+            amount: exact_amount(transfer),
+            status: transfer.status.as_str(),
+        })?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(feature = "parquet")]
+fn write_parquet(path: &Path, transfers: &[WhaleTransfer]) -> Result<()> {
+    use arrow_array::{
+        ArrayRef, Decimal128Array, RecordBatch, StringArray, TimestampSecondArray, UInt64Array,
+    };
+    use arrow_schema::{DataType, Field, Schema, TimeUnit};
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("chain", DataType::Utf8, false),
+        Field::new("chain_id", DataType::UInt64, false),
+        Field::new("block_number", DataType::UInt64, false),
+        Field::new(
+            "block_timestamp",
+            DataType::Timestamp(TimeUnit::Second, Some("UTC".into())),
+            true,
+        ),
+        Field::new("tx_hash", DataType::Utf8, false),
+        Field::new("log_index", DataType::UInt64, false),
+        Field::new("from", DataType::Utf8, false),
+        Field::new("from_label", DataType::Utf8, true),
+        Field::new("to", DataType::Utf8, false),
+        Field::new("to_label", DataType::Utf8, true),
+        Field::new("amount", DataType::Decimal128(38, USDC_DECIMALS as i8), false),
+        Field::new("status", DataType::Utf8, false),
+    ]));
+
+    let amounts = transfers
+        .iter()
+        .map(|t| {
+            i128::try_from(t.amount_raw)
+                .map_err(|_| eyre!("amount {} does not fit a Decimal128", t.amount_raw))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(
+            transfers.iter().map(|t| t.chain.name().to_lowercase()),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            transfers.iter().map(|t| t.chain.chain_id()),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            transfers.iter().map(|t| t.block_number),
+        )),
+        Arc::new(
+            TimestampSecondArray::from_iter(
+                transfers
+                    .iter()
+                    .map(|t| t.block_timestamp.map(|ts| ts.timestamp())),
+            )
+            .with_timezone("UTC"),
+        ),
+        Arc::new(StringArray::from_iter_values(
+            transfers.iter().map(|t| format!("{:?}", t.tx_hash)),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            transfers.iter().map(|t| t.log_index),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            transfers.iter().map(|t| format!("{:?}", t.from)),
+        )),
+        Arc::new(StringArray::from_iter(
+            transfers.iter().map(|t| t.from_label.as_deref()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            transfers.iter().map(|t| format!("{:?}", t.to)),
+        )),
+        Arc::new(StringArray::from_iter(
+            transfers.iter().map(|t| t.to_label.as_deref()),
+        )),
+        Arc::new(
+            Decimal128Array::from_iter_values(amounts)
+                .with_precision_and_scale(38, USDC_DECIMALS as i8)?,
+        ),
+        Arc::new(StringArray::from_iter_values(
+            transfers.iter().map(|t| t.status.as_str()),
+        )),
+    ];
+
+    let batch = RecordBatch::try_new(schema.clone(), columns)?;
+    let mut writer = ArrowWriter::try_new(File::create(path)?, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+#[cfg(not(feature = "parquet"))]
+fn write_parquet(_path: &Path, _transfers: &[WhaleTransfer]) -> Result<()> {
+    Err(eyre!(
+        "Parquet export requires building with `--features parquet`"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Address, B256, U256};
+    use chrono::DateTime;
+
+    #[test]
+    fn test_csv_partitions() {
+        let dir = tempfile::tempdir().unwrap();
+        let transfer = |chain, timestamp| {
+            WhaleTransfer::new(
+                chain,
+                B256::repeat_byte(1),
+                1,
+                Address::repeat_byte(2),
+                Address::repeat_byte(3),
+                U256::from(1_000_000_500_000u64),
+            )
+            .with_block_timestamp(timestamp)
+        };
+
+        let partitions = partition(vec![
+            transfer(Chain::Base, DateTime::from_timestamp(1_700_000_000, 0)),
+            transfer(Chain::Base, DateTime::from_timestamp(1_700_000_100, 0)),
+            transfer(Chain::Ethereum, None),
+        ]);
+        assert_eq!(partitions.len(), 2);
+
+        let base = &partitions[&(Chain::Base, "2023-11-14".to_string())];
+        let path = partition_dir(dir.path(), Chain::Base, "2023-11-14");
+        fs::create_dir_all(&path).unwrap();
+        write_csv(&path.join("transfers.csv"), base).unwrap();
+
+        let csv = fs::read_to_string(
+            dir.path()
+                .join("chain=base/date=2023-11-14/transfers.csv"),
+        )
+        .unwrap();
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().starts_with("chain,chain_id,block_number"));
+        assert!(lines.next().unwrap().contains(",1000000.500000,confirmed"));
+        assert_eq!(lines.count(), 1);
+    }
+
+    #[test]
+    fn test_missing_database_is_not_created() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("typo.db");
+        let args = ExportArgs {
+            db: Some(db.clone()),
+            input: None,
+            out_dir: dir.path().join("out"),
+            format: ExportFormat::Csv,
+            chain: Vec::new(),
+            since: None,
+            until: None,
+        };
+
+        assert!(run(args, &AppConfig::default()).is_err());
+        assert!(!db.exists());
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_round_trip() {
+        use arrow_array::{Array, Decimal128Array};
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("transfers.parquet");
+        let transfer = WhaleTransfer::new(
+            Chain::Arbitrum,
+            B256::repeat_byte(1),
+            1,
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            U256::from(2_000_000_000_001u64),
+        );
+        write_parquet(&path, &[transfer]).unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batch = reader.into_iter().next().unwrap().unwrap();
+        let amounts = batch
+            .column_by_name("amount")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Decimal128Array>()
+            .unwrap();
+        assert_eq!(amounts.value_as_string(0), "2000000.000001");
+        assert!(batch.column_by_name("block_timestamp").unwrap().is_null(0));
+    }
+}
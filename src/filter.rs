@@ -0,0 +1,35 @@
+use crate::labels::LabelCategory;
+use crate::types::{Chain, WhaleTransfer};
+
+use serde::Deserialize;
+
+/// Per-destination filter applied before a transfer is delivered
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TransferFilter {
+    /// Only deliver transfers of at least this many USD
+    pub min_amount_usd: Option<f64>,
+    /// Only deliver transfers on these chains (empty means all chains)
+    pub chains: Vec<Chain>,
+    /// Only deliver transfers with either side in these categories (empty means all)
+    pub categories: Vec<LabelCategory>,
+}
+
+impl TransferFilter {
+    /// Check whether a transfer passes this filter
+    pub fn matches(&self, transfer: &WhaleTransfer) -> bool {
+        if let Some(min) = self.min_amount_usd {
+            if transfer.amount_usd < min {
+                return false;
+            }
+        }
+
+        if !self.categories.is_empty()
+            && !self.categories.iter().any(|c| transfer.involves(*c))
+        {
+            return false;
+        }
+
+        self.chains.is_empty() || self.chains.contains(&transfer.chain)
+    }
+}
@@ -0,0 +1,149 @@
+use crate::config::FxConfig;
+use crate::types::FiatAmount;
+
+use eyre::{bail, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Where rates are loaded from
+#[derive(Debug, Clone)]
+enum Source {
+    Url(String),
+    File(PathBuf),
+}
+
+/// Latest USD exchange rates for the configured currencies
+#[derive(Debug, Default)]
+pub struct FxRates {
+    currencies: Vec<String>,
+    rates: RwLock<HashMap<String, f64>>,
+}
+
+impl FxRates {
+    /// Rates that convert nothing, used when `[fx]` is not configured
+    pub fn disabled() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Load the rates once and keep refreshing them in the background
+    ///
+    /// A failed load is logged and the previous rates are kept, so a flaky
+    /// source only makes the fiat amounts stale.
+    pub async fn start(config: &FxConfig) -> Result<Arc<Self>> {
+        let source = match (&config.url, &config.file) {
+            (Some(url), None) => Source::Url(url.clone()),
+            (None, Some(file)) => Source::File(file.clone()),
+            _ => bail!("[fx] needs exactly one of `url` or `file`"),
+        };
+        let fx = Arc::new(Self {
+            currencies: config.currencies.iter().map(|c| c.to_uppercase()).collect(),
+            rates: RwLock::new(HashMap::new()),
+        });
+
+        fx.refresh(&source).await;
+        let refresh = Duration::from_secs(config.refresh_secs.max(1));
+        let background = Arc::clone(&fx);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(refresh).await;
+                background.refresh(&source).await;
+            }
+        });
+
+        Ok(fx)
+    }
+
+    async fn refresh(&self, source: &Source) {
+        match load(source).await {
+            Ok(rates) => {
+                for currency in &self.currencies {
+                    if !rates.contains_key(currency) {
+                        tracing::warn!(%currency, "FX source has no rate for currency");
+                    }
+                }
+                *self.rates.write().expect("fx lock poisoned") = rates;
+            }
+            Err(e) => tracing::warn!(error = %e, "Failed to load FX rates"),
+        }
+    }
+
+    /// Convert a USD amount into every configured currency with a known rate
+    pub fn convert(&self, usd: f64) -> Vec<FiatAmount> {
+        let rates = self.rates.read().expect("fx lock poisoned");
+        self.currencies
+            .iter()
+            .filter_map(|currency| {
+                rates.get(currency).map(|rate| FiatAmount {
+                    currency: currency.clone(),
+                    amount: usd * rate,
+                })
+            })
+            .collect()
+    }
+}
+
+async fn load(source: &Source) -> Result<HashMap<String, f64>> {
+    let body = match source {
+        Source::Url(url) => reqwest::get(url).await?.error_for_status()?.text().await?,
+        Source::File(path) => tokio::fs::read_to_string(path).await?,
+    };
+    parse_rates(&body)
+}
+
+/// Parse `{"KRW": 1380.5, ...}` or `{"rates": {"KRW": 1380.5, ...}, ...}`
+fn parse_rates(body: &str) -> Result<HashMap<String, f64>> {
+    let value: Value = serde_json::from_str(body)?;
+    let object = match value.get("rates") {
+        Some(Value::Object(rates)) => rates,
+        _ => match &value {
+            Value::Object(rates) => rates,
+            _ => bail!("FX rates must be a JSON object"),
+        },
+    };
+
+    Ok(object
+        .iter()
+        .filter_map(|(currency, rate)| Some((currency.to_uppercase(), rate.as_f64()?)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::format_fiat;
+
+    #[test]
+    fn test_parse_rates() {
+        let nested = parse_rates(r#"{"base":"USD","rates":{"KRW":1380.5,"eur":0.92}}"#).unwrap();
+        assert_eq!(nested["KRW"], 1380.5);
+        assert_eq!(nested["EUR"], 0.92);
+
+        let flat = parse_rates(r#"{"JPY": 151.2, "note": "ignored"}"#).unwrap();
+        assert_eq!(flat.len(), 1);
+        assert!(parse_rates("[1, 2]").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_convert_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("rates.json");
+        std::fs::write(&file, r#"{"KRW": 1400, "EUR": 0.9, "JPY": 150}"#).unwrap();
+
+        let fx = FxRates::start(&FxConfig {
+            currencies: vec!["krw".to_string(), "eur".to_string(), "CHF".to_string()],
+            url: None,
+            file: Some(file),
+            refresh_secs: 3600,
+        })
+        .await
+        .unwrap();
+
+        let fiat = fx.convert(1_500_000.0);
+        assert_eq!(fiat.len(), 2);
+        assert_eq!(format_fiat(&fiat), "₩2,100,000,000 · €1,350,000");
+        assert!(FxRates::disabled().convert(1.0).is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+use crate::types::format_with_commas;
+
+use chrono::{DateTime, TimeZone};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// Output language
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    #[default]
+    En,
+    Ko,
+}
+
+impl Lang {
+    /// Language from `LC_ALL`, `LC_MESSAGES` or `LANG`, e.g. `ko_KR.UTF-8`
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|locale| match locale.get(..2) {
+                Some("ko") => Some(Lang::Ko),
+                Some("en") => Some(Lang::En),
+                _ => None,
+            })
+    }
+
+    /// ISO 639-1 code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Ko => "ko",
+        }
+    }
+}
+
+/// Message catalog for one language
+///
+/// Strings with `{}` are filled in order by [`fill`].
+#[derive(Debug)]
+pub struct Messages {
+    pub lang: Lang,
+    pub banner_title: &'static str,
+    pub banner_subtitle: &'static str,
+    pub whale_detected: &'static str,
+    pub whale_transfer_on: &'static str,
+    /// Amount and chain, for notification previews
+    pub whale_summary: &'static str,
+    pub amount: &'static str,
+    pub chain: &'static str,
+    pub from: &'static str,
+    pub to: &'static str,
+    pub tx: &'static str,
+    pub block: &'static str,
+    pub link: &'static str,
+    pub time: &'static str,
+    pub unknown: &'static str,
+    pub unknown_wallet: &'static str,
+    pub unknown_safe: &'static str,
+    pub unknown_clone: &'static str,
+    pub unknown_contract: &'static str,
+    pub view_transaction: &'static str,
+    /// Dashboard table headings
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    pub count: &'static str,
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    pub volume: &'static str,
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    pub entity: &'static str,
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    pub totals_by_chain: &'static str,
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    pub totals_by_entity: &'static str,
+    pub labels_loaded: &'static str,
+    pub whale_threshold: &'static str,
+    pub history_database: &'static str,
+    pub notification_channels: &'static str,
+    pub monitoring_chains: &'static str,
+    pub starting_monitors: &'static str,
+    /// `strftime` pattern for timestamps
+    pub datetime_format: &'static str,
+}
+
+static EN: Messages = Messages {
+    lang: Lang::En,
+    banner_title: "USDC WHALE DETECTOR",
+    banner_subtitle: "Monitoring large USDC transfers across chains",
+    whale_detected: "WHALE TRANSFER DETECTED",
+    whale_transfer_on: "Whale transfer on {}",
+    whale_summary: "{} on {}",
+    amount: "Amount",
+    chain: "Chain",
+    from: "From",
+    to: "To",
+    tx: "Tx",
+    block: "Block",
+    link: "Link",
+    time: "Time",
+    unknown: "Unknown",
+    unknown_wallet: "Unknown wallet",
+    unknown_safe: "Unknown Safe",
+    unknown_clone: "Unknown clone",
+    unknown_contract: "Unknown contract",
+    view_transaction: "View transaction",
+    count: "Count",
+    volume: "Volume",
+    entity: "Entity",
+    totals_by_chain: "Totals by chain",
+    totals_by_entity: "Totals by entity",
+    labels_loaded: "Loaded {} address labels",
+    whale_threshold: "Whale threshold:",
+    history_database: "History database: {} ({} transfers stored)",
+    notification_channels: "Sending to {} notification channels",
+    monitoring_chains: "Monitoring chains: {}",
+    starting_monitors: "Starting monitors...",
+    datetime_format: "%Y-%m-%d %H:%M:%S",
+};
+
+static KO: Messages = Messages {
+    lang: Lang::Ko,
+    banner_title: "USDC 고래 탐지기",
+    banner_subtitle: "여러 체인의 대규모 USDC 이체를 감시합니다",
+    whale_detected: "고래 이체 감지",
+    whale_transfer_on: "{} 고래 이체",
+    whale_summary: "{} ({})",
+    amount: "금액",
+    chain: "체인",
+    from: "보낸 주소",
+    to: "받는 주소",
+    tx: "트랜잭션",
+    block: "블록",
+    link: "링크",
+    time: "시간",
+    unknown: "알 수 없음",
+    unknown_wallet: "알 수 없는 지갑",
+    unknown_safe: "알 수 없는 Safe",
+    unknown_clone: "알 수 없는 클론",
+    unknown_contract: "알 수 없는 컨트랙트",
+    view_transaction: "트랜잭션 보기",
+    count: "건수",
+    volume: "거래액",
+    entity: "주체",
+    totals_by_chain: "체인별 합계",
+    totals_by_entity: "주체별 합계",
+    labels_loaded: "주소 라벨 {}개를 불러왔습니다",
+    whale_threshold: "고래 기준:",
+    history_database: "이력 데이터베이스: {} (저장된 이체 {}건)",
+    notification_channels: "알림 채널 {}개로 전송합니다",
+    monitoring_chains: "감시 중인 체인: {}",
+    starting_monitors: "모니터를 시작합니다...",
+    datetime_format: "%Y년 %m월 %d일 %H:%M:%S",
+};
+
+static CURRENT: OnceLock<&'static Messages> = OnceLock::new();
+
+/// Select the output language; only the first call has an effect
+pub fn init(lang: Lang) {
+    let _ = CURRENT.set(Messages::for_lang(lang));
+}
+
+/// Catalog of the selected language (English until [`init`] is called)
+pub fn messages() -> &'static Messages {
+    CURRENT.get().copied().unwrap_or(&EN)
+}
+
+impl Messages {
+    /// Catalog for a language
+    pub fn for_lang(lang: Lang) -> &'static Messages {
+        match lang {
+            Lang::En => &EN,
+            Lang::Ko => &KO,
+        }
+    }
+
+    /// Look up a message by field name, for templates
+    pub fn get(&self, key: &str) -> Option<&'static str> {
+        Some(match key {
+            "banner_title" => self.banner_title,
+            "banner_subtitle" => self.banner_subtitle,
+            "whale_detected" => self.whale_detected,
+            "whale_transfer_on" => self.whale_transfer_on,
+            "amount" => self.amount,
+            "chain" => self.chain,
+            "from" => self.from,
+            "to" => self.to,
+            "tx" => self.tx,
+            "block" => self.block,
+            "link" => self.link,
+            "time" => self.time,
+            "unknown" => self.unknown,
+            "unknown_wallet" => self.unknown_wallet,
+            "unknown_safe" => self.unknown_safe,
+            "unknown_clone" => self.unknown_clone,
+            "unknown_contract" => self.unknown_contract,
+            "view_transaction" => self.view_transaction,
+            _ => return None,
+        })
+    }
+
+    /// USD amount, e.g. `$1,500,000.00 USDC` or `$1,500,000.00 USDC (약 150만 달러)`
+    pub fn amount(&self, usd: f64) -> String {
+        let amount = format!("${} USDC", format_with_commas(usd));
+        match self.lang {
+            Lang::En => amount,
+            Lang::Ko => format!("{} (약 {} 달러)", amount, korean_units(usd)),
+        }
+    }
+
+    /// Timestamp in the catalog's date format
+    pub fn datetime<Tz: TimeZone>(&self, datetime: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        datetime.format(self.datetime_format).to_string()
+    }
+}
+
+/// Fill `{}` placeholders in order
+pub fn fill(message: &str, args: &[&dyn Display]) -> String {
+    let mut parts = message.split("{}");
+    let mut args = args.iter();
+    let mut filled = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            filled.push_str(&arg.to_string());
+        }
+        filled.push_str(part);
+    }
+    filled
+}
+
+/// Round to Korean myriad units, e.g. `2억 5,000만`
+pub fn korean_units(value: f64) -> String {
+    const UNITS: [(u64, &str); 3] = [(1_000_000_000_000, "조"), (100_000_000, "억"), (10_000, "만")];
+
+    let mut rest = value.round().max(0.0) as u64;
+    if rest < 10_000 {
+        return rest.to_string();
+    }
+
+    let mut parts = Vec::new();
+    for (unit, name) in UNITS {
+        let count = rest / unit;
+        if count > 0 {
+            let count = format_with_commas(count as f64);
+            parts.push(format!("{}{}", count.trim_end_matches(".00"), name));
+        }
+        rest %= unit;
+    }
+    parts.join(" ")
+}
+
+/// Terminal columns taken by a character; Hangul, CJK and emoji are two columns wide
+pub fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115F | 0x2E80..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F | 0xFF00..=0xFF60 | 0xFFE0..=0xFFE6 | 0x1F300..=0x1FAFF => 2,
+        _ => 1,
+    }
+}
+
+/// Terminal columns taken by text
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_korean_formatting() {
+        let ko = Messages::for_lang(Lang::Ko);
+        assert_eq!(korean_units(1_500_000.0), "150만");
+        assert_eq!(korean_units(250_000_000.0), "2억 5,000만");
+        assert_eq!(
+            ko.amount(1_500_000.0),
+            "$1,500,000.00 USDC (약 150만 달러)"
+        );
+        assert_eq!(fill(ko.history_database, &[&"whales.db", &42]), "이력 데이터베이스: whales.db (저장된 이체 42건)");
+
+        let date = Utc.with_ymd_and_hms(2024, 12, 8, 6, 30, 45).unwrap();
+        assert_eq!(ko.datetime(&date), "2024년 12월 08일 06:30:45");
+        assert_eq!(Messages::for_lang(Lang::En).datetime(&date), "2024-12-08 06:30:45");
+        assert_eq!(display_width("고래 a"), 6);
+    }
+}
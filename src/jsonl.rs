@@ -0,0 +1,130 @@
+use crate::types::WhaleTransfer;
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Writes whale transfers as JSON Lines to stdout or a size-rotated file
+pub enum JsonlWriter {
+    Stdout(io::Stdout),
+    File(RotatingFile),
+}
+
+impl JsonlWriter {
+    /// Create a writer for stdout
+    pub fn stdout() -> Self {
+        Self::Stdout(io::stdout())
+    }
+
+    /// Create a writer for a file, rotating after `max_bytes`
+    pub fn file<P: AsRef<Path>>(path: P, max_bytes: u64, keep: usize) -> io::Result<Self> {
+        Ok(Self::File(RotatingFile::open(path, max_bytes, keep)?))
+    }
+
+    /// Write a single transfer as one line
+    pub fn write(&mut self, transfer: &WhaleTransfer) -> eyre::Result<()> {
+        let mut line = serde_json::to_vec(transfer)?;
+        line.push(b'\n');
+
+        match self {
+            Self::Stdout(stdout) => {
+                let mut lock = stdout.lock();
+                lock.write_all(&line)?;
+                lock.flush()?;
+            }
+            Self::File(file) => file.write_line(&line)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Append-only file that is rotated to `path.1`, `path.2`, … when it gets too large
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    keep: usize,
+}
+
+impl RotatingFile {
+    /// Open (or create) the file in append mode
+    pub fn open<P: AsRef<Path>>(path: P, max_bytes: u64, keep: usize) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            file,
+            written,
+            max_bytes,
+            keep,
+        })
+    }
+
+    /// Append a line, rotating first if it would exceed the size limit
+    pub fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        if self.written > 0 && self.written + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+
+        self.file.write_all(line)?;
+        self.file.flush()?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.keep == 0 {
+            self.file = File::create(&self.path)?;
+            self.written = 0;
+            return Ok(());
+        }
+
+        // Shift path.N-1 -> path.N, dropping the oldest
+        let _ = fs::remove_file(self.rotated_path(self.keep));
+        for index in (1..self.keep).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated_path(1))?;
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("whales.jsonl");
+        let mut file = RotatingFile::open(&path, 10, 2).unwrap();
+
+        for line in ["aaaaaa\n", "bbbbbb\n", "cccccc\n", "dddddd\n"] {
+            file.write_line(line.as_bytes()).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "dddddd\n");
+        assert_eq!(fs::read_to_string(dir.path().join("whales.jsonl.1")).unwrap(), "cccccc\n");
+        assert_eq!(fs::read_to_string(dir.path().join("whales.jsonl.2")).unwrap(), "bbbbbb\n");
+        assert!(!dir.path().join("whales.jsonl.3").exists());
+    }
+}
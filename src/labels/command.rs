@@ -0,0 +1,329 @@
+use super::format::{self, format_key, IssueKind, LabelFormat};
+use super::{standard_files, LabelRecord, LabelStore};
+use crate::cli::{
+    LabelsAddArgs, LabelsArgs, LabelsCommand, LabelsExportArgs, LabelsImportArgs, LabelsLintArgs,
+    LabelsRemoveArgs, LabelsSearchArgs,
+};
+use crate::config::AppConfig;
+use crate::console::fit;
+
+use eyre::{bail, Result};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Run the `labels` subcommand
+pub fn run(args: LabelsArgs, config: &AppConfig) -> Result<()> {
+    match args.command {
+        LabelsCommand::Import(args) => import(args),
+        LabelsCommand::Export(args) => export(args, config),
+        LabelsCommand::Add(args) => add(args),
+        LabelsCommand::Remove(args) => remove(args),
+        LabelsCommand::Search(args) => search(args, config),
+        LabelsCommand::Lint(args) => lint(args, config),
+    }
+}
+
+/// Merge label files into a JSON label file
+fn import(args: LabelsImportArgs) -> Result<()> {
+    let mut layers = Vec::new();
+    if args.into.exists() {
+        layers.push((args.into.display().to_string(), load_for_edit(&args.into)?));
+    }
+
+    for path in &args.files {
+        let format = args.format.unwrap_or_else(|| LabelFormat::from_path(path));
+        let mut store = LabelStore::load_from_path(path, format, false)?;
+        let labels = std::mem::take(&mut store.labels);
+        for ((chain, address), mut record) in labels {
+            if record.source.is_none() {
+                record.source.clone_from(&args.source);
+            }
+            store.labels.insert((chain.or(args.chain), address), record);
+        }
+        eprintln!("{:>6} labels <- {}", store.len(), path.display());
+        layers.push((path.display().to_string(), store));
+    }
+
+    let merged = LabelStore::merge(layers);
+    for conflict in &merged.conflicts {
+        eprintln!("conflict: {}", conflict);
+    }
+
+    save(&merged.store, &args.into, LabelFormat::Json)?;
+    eprintln!("{:>6} labels -> {}", merged.store.len(), args.into.display());
+    Ok(())
+}
+
+/// Write the merged label store from every configured source
+fn export(args: LabelsExportArgs, config: &AppConfig) -> Result<()> {
+    let layered = LabelStore::load_layered(&config.labels)?;
+    let format = args.format.unwrap_or_else(|| match &args.output {
+        Some(path) => LabelFormat::from_path(path),
+        None => LabelFormat::Json,
+    });
+
+    match &args.output {
+        Some(path) => {
+            save(&layered.store, path, format)?;
+            eprintln!("{:>6} labels -> {}", layered.store.len(), path.display());
+        }
+        None => write(&layered.store, format, io::stdout().lock())?,
+    }
+    Ok(())
+}
+
+/// Add or replace one label
+fn add(args: LabelsAddArgs) -> Result<()> {
+    if args.label.trim().is_empty() {
+        bail!("label must not be empty");
+    }
+    if let Some(confidence) = args.confidence {
+        if !(0.0..=1.0).contains(&confidence) {
+            bail!("confidence must be between 0 and 1");
+        }
+    }
+
+    let mut store = load_for_edit(&args.file)?;
+    let key = format_key(args.chain, &args.address);
+    let record = LabelRecord {
+        entity: args.entity,
+        category: args.category,
+        source: args.source,
+        confidence: args.confidence,
+        ..LabelRecord::new(args.label.trim().to_string())
+    };
+    let label = record.label.clone();
+
+    if let Some(previous) = store.insert(args.chain, args.address, record) {
+        if !args.force {
+            bail!(
+                "{} is already labeled {:?} in {}; use --force to replace it",
+                key,
+                previous.label,
+                args.file.display()
+            );
+        }
+        eprintln!("Replaced {:?}", previous.label);
+    }
+
+    save(&store, &args.file, LabelFormat::from_path(&args.file))?;
+    eprintln!("{}: {:?} -> {}", key, label, args.file.display());
+    Ok(())
+}
+
+/// Remove one label
+fn remove(args: LabelsRemoveArgs) -> Result<()> {
+    let mut store = load_for_edit(&args.file)?;
+    let key = format_key(args.chain, &args.address);
+
+    let Some(removed) = store.remove(args.chain, &args.address) else {
+        bail!("{} has no label in {}", key, args.file.display());
+    };
+    save(&store, &args.file, LabelFormat::from_path(&args.file))?;
+    eprintln!("Removed {}: {:?}", key, removed.label);
+
+    if let Some(chain) = args.chain {
+        if let Some(global) = store.record(chain, &args.address) {
+            eprintln!("The global label {:?} still applies", global.label);
+        }
+    }
+    Ok(())
+}
+
+/// Print labels matching a name, entity or address
+fn search(args: LabelsSearchArgs, config: &AppConfig) -> Result<()> {
+    let store = LabelStore::load_layered(&config.labels)?.store;
+    let query = args.query.trim().to_lowercase();
+
+    let matches: Vec<_> = store
+        .sorted()
+        .into_iter()
+        .filter(|((chain, address), record)| {
+            let text_match = if query.starts_with("0x") {
+                address.to_checksum(None).to_lowercase().starts_with(&query)
+            } else {
+                record.label.to_lowercase().contains(&query)
+                    || record
+                        .entity
+                        .as_ref()
+                        .is_some_and(|e| e.to_lowercase().contains(&query))
+            };
+            text_match
+                && args.chain.is_none_or(|c| match chain {
+                    Some(scope) => *scope == c,
+                    None => !store.hides(c, address),
+                })
+                && args.category.is_none_or(|c| record.category == Some(c))
+        })
+        .collect();
+
+    let mut out = io::stdout().lock();
+    writeln!(
+        out,
+        "{:<42}  {:<8}  {}  {}  {:<12}  SOURCE",
+        "ADDRESS",
+        "CHAIN",
+        fit("LABEL", 32),
+        fit("ENTITY", 16),
+        "CATEGORY"
+    )?;
+    for ((chain, address), record) in &matches {
+        writeln!(
+            out,
+            "{:<42}  {:<8}  {}  {}  {:<12}  {}",
+            address.to_checksum(None),
+            chain.map(|c| c.name().to_lowercase()).unwrap_or_else(|| "*".to_string()),
+            fit(&record.label, 32),
+            fit(record.entity.as_deref().unwrap_or("-"), 16),
+            record.category.map(|c| c.as_str()).unwrap_or("-"),
+            record.source.as_deref().unwrap_or("-"),
+        )?;
+    }
+    writeln!(out, "{} labels", matches.len())?;
+    Ok(())
+}
+
+/// Report problems in label files, optionally rewriting fixable ones
+fn lint(args: LabelsLintArgs, config: &AppConfig) -> Result<()> {
+    let files = if args.files.is_empty() {
+        let mut files = standard_files();
+        files.extend(config.labels.files.iter().cloned());
+        files
+    } else {
+        args.files
+    };
+    if files.is_empty() {
+        bail!("no label files to check");
+    }
+
+    let (mut errors, mut warnings) = (0, 0);
+    for path in &files {
+        let format = LabelFormat::from_path(path);
+        let parsed = match format::read(path, format) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                println!("{}: error: {}", path.display(), e);
+                errors += 1;
+                continue;
+            }
+        };
+
+        for issue in &parsed.issues {
+            let severity = if issue.is_error() { "error" } else { "warning" };
+            println!("{}: {}: {}", path.display(), severity, issue);
+        }
+        let file_errors = parsed.issues.iter().filter(|i| i.is_error()).count();
+        let invalid = parsed.issues.iter().any(|i| i.kind == IssueKind::Invalid);
+        errors += file_errors;
+        warnings += parsed.issues.len() - file_errors;
+
+        if args.fix && !parsed.issues.is_empty() {
+            if let Some(layout) = parsed.foreign {
+                println!("{}: not fixed, the {} layout is only read", path.display(), layout);
+                continue;
+            }
+            if invalid {
+                println!("{}: not fixed, remove the invalid entries first", path.display());
+                continue;
+            }
+            let fixed = parsed.issues.len();
+            save(&parsed.store(), path, format)?;
+            println!("{}: fixed {} issue(s)", path.display(), fixed);
+            errors -= file_errors;
+            warnings -= fixed - file_errors;
+        }
+    }
+
+    println!(
+        "{} file(s) checked, {} error(s), {} warning(s)",
+        files.len(),
+        errors,
+        warnings
+    );
+    if errors > 0 {
+        bail!("label files have errors");
+    }
+    Ok(())
+}
+
+/// Load a label file that is about to be rewritten
+///
+/// Refuses files with invalid entries, which would be dropped, and files in
+/// a third-party layout, which would be rewritten in ours.
+fn load_for_edit(path: &Path) -> Result<LabelStore> {
+    if !path.exists() {
+        return Ok(LabelStore::new());
+    }
+    let parsed = format::read(path, LabelFormat::from_path(path))?;
+    if let Some(layout) = parsed.foreign {
+        bail!(
+            "{} is in the {} layout, which is only read; import it into a label file with `labels import`",
+            path.display(),
+            layout
+        );
+    }
+    parsed.into_store(&path.display().to_string(), true).map_err(|e| {
+        eyre::eyre!("{}\nrun `labels lint` and fix the file before editing it", e)
+    })
+}
+
+/// Write a label file through a temporary file, creating its directory
+///
+/// A failed or interrupted write leaves the previous file untouched.
+fn save(store: &LabelStore, path: &Path, format: LabelFormat) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let result = File::create(&tmp).map_err(eyre::Report::from).and_then(|file| {
+        let mut out = BufWriter::new(file);
+        write(store, format, &mut out)?;
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        Ok(())
+    });
+    if let Err(e) = result.and_then(|()| Ok(fs::rename(&tmp, path)?)) {
+        let _ = fs::remove_file(&tmp);
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn write<W: Write>(store: &LabelStore, format: LabelFormat, out: W) -> Result<()> {
+    match format {
+        LabelFormat::Json => format::write_json(store, out),
+        LabelFormat::Csv => format::write_csv(store, out),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leaves_third_party_files_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let eth_labels = dir.path().join("eth-labels.json");
+        fs::write(
+            &eth_labels,
+            r#"[{"address": "0x28C6c06298d514Db089934071355E5743bf21d60", "chainId": 56, "nameTag": "Binance 14"}]"#,
+        )
+        .unwrap();
+        let error = load_for_edit(&eth_labels).unwrap_err().to_string();
+        assert!(error.contains("eth-labels layout"), "{}", error);
+
+        // The lowercase address is a fixable warning, but fixing would rewrite the dump
+        let etherscan = dir.path().join("etherscan.json");
+        let dump = r#"{"0x28c6c06298d514db089934071355e5743bf21d60": {"name": "Binance 14", "labels": ["exchange"]}}"#;
+        fs::write(&etherscan, dump).unwrap();
+        let args = LabelsLintArgs {
+            files: vec![etherscan.clone()],
+            fix: true,
+        };
+        lint(args, &AppConfig::default()).unwrap();
+        assert_eq!(fs::read_to_string(&etherscan).unwrap(), dump);
+    }
+}
@@ -0,0 +1,671 @@
+use super::{LabelCategory, LabelRecord, LabelStore};
+use crate::types::Chain;
+
+use alloy::primitives::Address;
+use clap::ValueEnum;
+use eyre::{bail, Result};
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Optional chain scope and address of a label
+pub type LabelKey = (Option<Chain>, Address);
+
+/// A label keyed by optional chain scope and address
+pub type LabelEntry = (LabelKey, LabelRecord);
+
+/// Label file layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LabelFormat {
+    /// JSON object keyed by address; also reads eth-labels and etherscan-labels dumps
+    Json,
+    /// CSV with a header row: address, label, entity, category, chain, source, confidence
+    Csv,
+}
+
+impl LabelFormat {
+    /// Guess the format from a file extension, defaulting to JSON
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => LabelFormat::Csv,
+            _ => LabelFormat::Json,
+        }
+    }
+}
+
+/// What is wrong with an entry in a label file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    /// The entry can't be used and is skipped
+    Invalid,
+    /// The address was already labeled earlier in the file; the later entry wins
+    Duplicate,
+    /// The address is not EIP-55 checksummed
+    Checksum,
+}
+
+/// A problem with one entry of a label file
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelIssue {
+    /// Where in the file: a key, `[index]` or `line N`
+    pub location: String,
+    pub kind: IssueKind,
+    pub message: String,
+}
+
+impl LabelIssue {
+    /// Whether the issue makes the file fail in strict mode
+    pub fn is_error(&self) -> bool {
+        self.kind != IssueKind::Checksum
+    }
+}
+
+impl fmt::Display for LabelIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// CSV columns written by [`write_csv`]
+const CSV_COLUMNS: [&str; 7] = ["address", "label", "entity", "category", "chain", "source", "confidence"];
+
+/// Entries read from a label file together with the problems found
+#[derive(Debug, Default)]
+pub struct Parsed {
+    pub entries: Vec<LabelEntry>,
+    /// Chain-scoped `null` entries hiding the global label on that chain
+    pub hidden: Vec<(Chain, Address)>,
+    pub issues: Vec<LabelIssue>,
+    /// Third-party layout of the file, which rewriting it would not preserve
+    pub foreign: Option<&'static str>,
+    /// Location of the first entry for each key
+    seen: HashMap<(Option<Chain>, Address), String>,
+}
+
+impl Parsed {
+    fn issue(&mut self, location: &str, kind: IssueKind, message: impl Into<String>) {
+        self.issues.push(LabelIssue {
+            location: location.to_string(),
+            kind,
+            message: message.into(),
+        });
+    }
+
+    /// Validate and add an entry
+    fn push(&mut self, location: &str, key: (Option<Chain>, Address), record: LabelRecord) {
+        if record.label.trim().is_empty() {
+            return self.issue(location, IssueKind::Invalid, "empty label");
+        }
+        if let Some(confidence) = record.confidence {
+            if !(0.0..=1.0).contains(&confidence) {
+                return self.issue(
+                    location,
+                    IssueKind::Invalid,
+                    format!("confidence {} is not between 0 and 1", confidence),
+                );
+            }
+        }
+        self.check_duplicate(location, key);
+        if let Some(chain) = key.0 {
+            self.hidden.retain(|hidden| *hidden != (chain, key.1));
+        }
+        self.entries.push((key, record));
+    }
+
+    /// Add a tombstone hiding the global label of an address on one chain
+    fn hide(&mut self, location: &str, key: (Option<Chain>, Address)) {
+        let Some(chain) = key.0 else {
+            return self.issue(location, IssueKind::Invalid, "only a chain-scoped label can be null or empty");
+        };
+        self.check_duplicate(location, key);
+        self.entries.retain(|(entry_key, _)| *entry_key != key);
+        self.hidden.push((chain, key.1));
+    }
+
+    /// Note a key that appeared earlier in the file
+    fn check_duplicate(&mut self, location: &str, key: (Option<Chain>, Address)) {
+        match self.seen.get(&key) {
+            Some(first) => {
+                let message = format!("duplicate of {}, this entry wins", first);
+                self.issue(location, IssueKind::Duplicate, message);
+            }
+            None => {
+                self.seen.insert(key, location.to_string());
+            }
+        }
+    }
+
+    /// Note an address that isn't written in its checksummed form
+    fn check_checksum(&mut self, location: &str, raw: &str, address: &Address) {
+        let checksummed = address.to_checksum(None);
+        if raw.trim() != checksummed {
+            self.issue(location, IssueKind::Checksum, format!("should be {}", checksummed));
+        }
+    }
+
+    /// Build a store, warning about problems or rejecting them in strict mode
+    pub fn into_store(self, source: &str, strict: bool) -> Result<LabelStore> {
+        let errors: Vec<String> = self
+            .issues
+            .iter()
+            .filter(|issue| issue.is_error())
+            .map(ToString::to_string)
+            .collect();
+        if strict && !errors.is_empty() {
+            bail!(
+                "{} problem(s) in labels from {}:\n  {}",
+                errors.len(),
+                source,
+                errors.join("\n  ")
+            );
+        }
+        for error in &errors {
+            tracing::warn!("Labels from {}: {}", source, error);
+        }
+        Ok(self.store())
+    }
+
+    /// Build a store from the valid entries and tombstones
+    pub fn store(self) -> LabelStore {
+        LabelStore {
+            hidden: self.hidden.into_iter().collect(),
+            ..LabelStore::from_entries(self.entries)
+        }
+    }
+}
+
+/// Read a label file in the given format
+pub fn read(path: &Path, format: LabelFormat) -> Result<Parsed> {
+    match format {
+        LabelFormat::Json => read_json(&fs::read_to_string(path)?),
+        LabelFormat::Csv => read_csv(File::open(path)?),
+    }
+}
+
+/// Read labels from JSON in any of the supported layouts
+///
+/// - Our own: `{"[chain:]0x…": "Name" | {"label": …, "category": …}}`, where
+///   `"chain:0x…": null` hides the global label of the address on that chain
+/// - etherscan-labels: `{"0x…": {"name": "Binance 14", "labels": ["exchange"]}}`
+/// - eth-labels: `[{"address": "0x…", "chainId": 1, "label": "exchange", "nameTag": "Binance 14"}]`
+///
+/// eth-labels entries for chains we don't monitor are skipped silently.
+pub fn read_json(json: &str) -> Result<Parsed> {
+    let mut parsed = Parsed::default();
+
+    match serde_json::from_str(json)? {
+        RawJson::Object(map) => {
+            for (key_str, value) in map {
+                let location = format!("{:?}", key_str);
+                let key = match parse_key(&key_str) {
+                    Ok(key) => key,
+                    Err(e) => {
+                        parsed.issue(&location, IssueKind::Invalid, e);
+                        continue;
+                    }
+                };
+                let raw_address = key_str.rsplit(':').next().unwrap_or_default();
+                parsed.check_checksum(&location, raw_address, &key.1);
+
+                let record = match value {
+                    Value::Null => {
+                        parsed.hide(&location, key);
+                        continue;
+                    }
+                    Value::String(label) => LabelRecord::new(label),
+                    Value::Object(ref object) if object.contains_key("label") => {
+                        match serde_json::from_value(value) {
+                            Ok(record) => record,
+                            Err(e) => {
+                                parsed.issue(&location, IssueKind::Invalid, e.to_string());
+                                continue;
+                            }
+                        }
+                    }
+                    Value::Object(object) => match object.get("name").and_then(Value::as_str) {
+                        Some(name) => {
+                            parsed.foreign = Some("etherscan-labels");
+                            tagged_record(name, object.get("labels"), "etherscan-labels")
+                        }
+                        None => {
+                            parsed.issue(&location, IssueKind::Invalid, "no label or name");
+                            continue;
+                        }
+                    },
+                    _ => {
+                        parsed.issue(&location, IssueKind::Invalid, "not a string or object");
+                        continue;
+                    }
+                };
+                parsed.push(&location, key, record);
+            }
+        }
+        RawJson::Array(items) => {
+            parsed.foreign = Some("eth-labels");
+            for (index, item) in items.iter().enumerate() {
+                let location = format!("[{}]", index);
+                match eth_labels_entry(item) {
+                    Ok(Some((key, record))) => parsed.push(&location, key, record),
+                    Ok(None) => {}
+                    Err(e) => parsed.issue(&location, IssueKind::Invalid, e),
+                }
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Top level of a JSON label file, keeping duplicate keys
+enum RawJson {
+    Object(Vec<(String, Value)>),
+    Array(Vec<Value>),
+}
+
+impl<'de> Deserialize<'de> for RawJson {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct RawVisitor;
+
+        impl<'de> Visitor<'de> for RawVisitor {
+            type Value = RawJson;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a JSON object or array of labels")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<RawJson, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(RawJson::Object(entries))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<RawJson, A::Error> {
+                let mut items = Vec::new();
+                while let Some(item) = seq.next_element()? {
+                    items.push(item);
+                }
+                Ok(RawJson::Array(items))
+            }
+        }
+
+        deserializer.deserialize_any(RawVisitor)
+    }
+}
+
+/// Parse a label key: an address, optionally prefixed with `chain:`
+fn parse_key(key: &str) -> std::result::Result<(Option<Chain>, Address), String> {
+    let (chain, address) = match key.split_once(':') {
+        Some((chain, address)) => (Some(chain.trim().parse::<Chain>()?), address),
+        None => (None, key),
+    };
+    Ok((chain, parse_address(address)?))
+}
+
+/// Parse an address with or without checksum
+pub fn parse_address(value: &str) -> std::result::Result<Address, String> {
+    Address::from_str(&value.trim().to_lowercase())
+        .map_err(|_| format!("invalid address {:?}", value.trim()))
+}
+
+/// Convert one eth-labels item; `label` there is a tag such as `binance` or `exchange`
+///
+/// Returns `None` for chains we don't monitor.
+fn eth_labels_entry(item: &Value) -> std::result::Result<Option<LabelEntry>, String> {
+    let address = item
+        .get("address")
+        .and_then(Value::as_str)
+        .ok_or("missing address")?;
+    let address = parse_address(address)?;
+    let chain = match item.get("chainId").and_then(Value::as_u64) {
+        Some(id) => match Chain::from_chain_id(id) {
+            Some(chain) => Some(chain),
+            None => return Ok(None),
+        },
+        None => None,
+    };
+    let tag = item.get("label").and_then(Value::as_str);
+    let name = item
+        .get("nameTag")
+        .and_then(Value::as_str)
+        .filter(|name| !name.is_empty())
+        .or(tag)
+        .ok_or("missing nameTag and label")?;
+    let tags = tag.map(|tag| json!([tag]));
+    Ok(Some(((chain, address), tagged_record(name, tags.as_ref(), "eth-labels"))))
+}
+
+/// Build a record from a name and a list of free-form tags
+fn tagged_record(name: &str, tags: Option<&Value>, source: &str) -> LabelRecord {
+    let category = tags
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .find_map(|tag| tag.parse().ok());
+
+    LabelRecord {
+        category,
+        source: Some(source.to_string()),
+        ..LabelRecord::new(name.to_string())
+    }
+}
+
+/// Read labels from CSV with a header row
+///
+/// Columns are matched by name, case-insensitively: `address` and `label`
+/// (or `name`) are required; `entity`, `category`, `chain` (name or chain id),
+/// `source` and `confidence` are optional. A row with a chain and an empty
+/// label hides the global label of the address on that chain.
+pub fn read_csv<R: Read>(reader: R) -> Result<Parsed> {
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(reader);
+    let headers: Vec<String> = reader
+        .headers()?
+        .iter()
+        .map(|h| h.to_lowercase().replace(' ', "_"))
+        .collect();
+    let column = |names: &[&str]| headers.iter().position(|h| names.contains(&h.as_str()));
+
+    let Some(address_col) = column(&["address"]) else {
+        bail!("CSV labels need an `address` column");
+    };
+    let Some(label_col) = column(&["label", "name", "name_tag"]) else {
+        bail!("CSV labels need a `label` column");
+    };
+    let entity_col = column(&["entity"]);
+    let category_col = column(&["category", "type"]);
+    let chain_col = column(&["chain", "chain_id"]);
+    let source_col = column(&["source"]);
+    let confidence_col = column(&["confidence"]);
+
+    let mut parsed = Parsed::default();
+    if headers.iter().any(|h| !CSV_COLUMNS.contains(&h.as_str())) {
+        parsed.foreign = Some("third-party CSV");
+    }
+    for (index, row) in reader.records().enumerate() {
+        let row = row?;
+        let location = format!("line {}", index + 2);
+        let field = |col: Option<usize>| {
+            col.and_then(|c| row.get(c))
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        let raw_address = field(Some(address_col)).unwrap_or_default();
+        let address = match parse_address(&raw_address) {
+            Ok(address) => address,
+            Err(e) => {
+                parsed.issue(&location, IssueKind::Invalid, e);
+                continue;
+            }
+        };
+        let chain = match field(chain_col).map(|chain| parse_chain(&chain)) {
+            Some(Ok(chain)) => Some(chain),
+            Some(Err(e)) => {
+                parsed.issue(&location, IssueKind::Invalid, e);
+                continue;
+            }
+            None => None,
+        };
+        let category = match field(category_col).map(|category| category.parse()) {
+            Some(Ok(category)) => Some(category),
+            Some(Err(e)) => {
+                parsed.issue(&location, IssueKind::Invalid, e);
+                continue;
+            }
+            None => None,
+        };
+        let confidence = match field(confidence_col).map(|c| c.parse::<f64>()) {
+            Some(Ok(confidence)) => Some(confidence),
+            Some(Err(_)) => {
+                parsed.issue(&location, IssueKind::Invalid, "confidence is not a number");
+                continue;
+            }
+            None => None,
+        };
+        parsed.check_checksum(&location, &raw_address, &address);
+
+        let Some(label) = field(Some(label_col)) else {
+            parsed.hide(&location, (chain, address));
+            continue;
+        };
+        let record = LabelRecord {
+            entity: field(entity_col),
+            category,
+            source: field(source_col),
+            confidence,
+            ..LabelRecord::new(label)
+        };
+        parsed.push(&location, (chain, address), record);
+    }
+
+    Ok(parsed)
+}
+
+/// Parse a chain name or EIP-155 chain id
+fn parse_chain(value: &str) -> std::result::Result<Chain, String> {
+    match value.parse::<u64>() {
+        Ok(id) => Chain::from_chain_id(id).ok_or_else(|| format!("unsupported chain id {}", id)),
+        Err(_) => value.parse(),
+    }
+}
+
+/// Format a label key as `0x…` or `chain:0x…` with a checksummed address
+pub fn format_key(chain: Option<Chain>, address: &Address) -> String {
+    match chain {
+        Some(chain) => format!("{}:{}", chain.name().to_lowercase(), address.to_checksum(None)),
+        None => address.to_checksum(None),
+    }
+}
+
+/// Write labels as a JSON object with one entry per line, sorted by key
+///
+/// Records with only a label are written as a bare string, hidden global
+/// labels as `null`.
+pub fn write_json<W: Write>(store: &LabelStore, mut out: W) -> Result<()> {
+    let entries = with_hidden(store);
+    writeln!(out, "{{")?;
+    for (i, ((chain, address), record)) in entries.iter().enumerate() {
+        let separator = if i + 1 < entries.len() { "," } else { "" };
+        writeln!(
+            out,
+            "  {}: {}{}",
+            json!(format_key(*chain, address)),
+            record.map_or_else(|| "null".to_string(), record_json),
+            separator
+        )?;
+    }
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// Render a record on one line with fields in a fixed order
+fn record_json(record: &LabelRecord) -> String {
+    let fields = [
+        ("entity", record.entity.as_ref().map(|e| json!(e))),
+        ("category", record.category.map(|c| json!(c.as_str()))),
+        ("source", record.source.as_ref().map(|s| json!(s))),
+        ("confidence", record.confidence.map(|c| json!(c))),
+    ];
+    let mut parts = vec![format!("\"label\": {}", json!(record.label))];
+    parts.extend(
+        fields
+            .into_iter()
+            .filter_map(|(name, value)| Some(format!("\"{}\": {}", name, value?))),
+    );
+
+    if parts.len() == 1 {
+        json!(record.label).to_string()
+    } else {
+        format!("{{ {} }}", parts.join(", "))
+    }
+}
+
+/// Write labels as CSV with a header row, sorted by key
+///
+/// Hidden global labels are written as rows with an empty label.
+pub fn write_csv<W: Write>(store: &LabelStore, out: W) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(CSV_COLUMNS)?;
+    for ((chain, address), record) in with_hidden(store) {
+        let record = record.cloned().unwrap_or_else(|| LabelRecord::new(String::new()));
+        writer.write_record([
+            address.to_checksum(None),
+            record.label,
+            record.entity.unwrap_or_default(),
+            record.category.map(|c| c.as_str().to_string()).unwrap_or_default(),
+            chain.map(|c| c.name().to_lowercase()).unwrap_or_default(),
+            record.source.unwrap_or_default(),
+            record.confidence.map(|c| c.to_string()).unwrap_or_default(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Labels and tombstones of a store, sorted by key
+fn with_hidden(store: &LabelStore) -> Vec<(LabelKey, Option<&LabelRecord>)> {
+    let mut entries: Vec<_> = store
+        .sorted()
+        .into_iter()
+        .map(|(key, record)| (*key, Some(record)))
+        .chain(store.hidden.iter().map(|(chain, address)| ((Some(*chain), *address), None)))
+        .collect();
+    entries.sort_by_key(|(key, _)| *key);
+    entries
+}
+
+impl FromStr for LabelCategory {
+    type Err = String;
+
+    /// Parse a category name or a common tag such as `exchange` or `dex`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace([' ', '-'], "_").as_str() {
+            "cex" | "exchange" | "centralized_exchange" => Ok(LabelCategory::Cex),
+            "defi" | "dex" | "lending" | "dex_aggregator" => Ok(LabelCategory::Defi),
+            "bridge" => Ok(LabelCategory::Bridge),
+            "market_maker" | "mm" => Ok(LabelCategory::MarketMaker),
+            "issuer" | "stablecoin_issuer" => Ok(LabelCategory::Issuer),
+            "fund" | "vc" => Ok(LabelCategory::Fund),
+            other => Err(format!("unknown label category: {}", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BINANCE: &str = "0x28C6c06298d514Db089934071355E5743bf21d60";
+
+    #[test]
+    fn test_read_public_layouts() {
+        let etherscan = format!(
+            r#"{{"{}": {{"name": "Binance 14", "labels": ["binance", "exchange"]}}}}"#,
+            BINANCE
+        );
+        let parsed = read_json(&etherscan).unwrap();
+        assert_eq!(parsed.foreign, Some("etherscan-labels"));
+        let entries = parsed.entries;
+        assert_eq!(entries[0].0 .0, None);
+        assert_eq!(entries[0].1.label, "Binance 14");
+        assert_eq!(entries[0].1.category, Some(LabelCategory::Cex));
+
+        let eth_labels = format!(
+            r#"[
+                {{"address": "{}", "chainId": 8453, "label": "bridge", "nameTag": "Base Bridge"}},
+                {{"address": "{}", "chainId": 56, "label": "binance", "nameTag": "Binance"}}
+            ]"#,
+            BINANCE, BINANCE
+        );
+        let parsed = read_json(&eth_labels).unwrap();
+        assert!(parsed.issues.is_empty());
+        assert_eq!(parsed.foreign, Some("eth-labels"));
+        let entries = parsed.entries;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0 .0, Some(Chain::Base));
+        assert_eq!(entries[0].1.category, Some(LabelCategory::Bridge));
+        assert_eq!(entries[0].1.source.as_deref(), Some("eth-labels"));
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let csv = format!(
+            "Address,Label,Category,Chain,Confidence\n\
+             {b},Binance 14,cex,,0.9\n\
+             {b},Binance on Base,exchange,8453,\n\
+             0x1234,Broken,,,\n\
+             {b},Nowhere,,solana,\n",
+            b = BINANCE
+        );
+        let parsed = read_csv(csv.as_bytes()).unwrap();
+        assert_eq!(parsed.entries.len(), 2);
+        assert_eq!(parsed.issues.len(), 2);
+        assert!(parsed.into_store("test.csv", true).is_err());
+
+        let store = LabelStore::from_entries(read_csv(csv.as_bytes()).unwrap().entries);
+        let mut out = Vec::new();
+        write_csv(&store, &mut out).unwrap();
+        let written = String::from_utf8(out).unwrap();
+        assert_eq!(
+            written.lines().nth(1).unwrap(),
+            format!("{},Binance 14,,cex,,,0.9", BINANCE)
+        );
+
+        let reread = read_csv(written.as_bytes()).unwrap();
+        assert!(reread.issues.is_empty());
+        assert_eq!(reread.foreign, None);
+        let renamed = format!("address,name,type\n{},Binance 14,cex\n", BINANCE);
+        assert_eq!(read_csv(renamed.as_bytes()).unwrap().foreign, Some("third-party CSV"));
+        let reread = reread.into_store("written.csv", true).unwrap();
+        let mut json = Vec::new();
+        write_json(&reread, &mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.contains(&format!(
+            "\"base:{}\": {{ \"label\": \"Binance on Base\", \"category\": \"cex\" }}",
+            BINANCE
+        )));
+        assert_eq!(LabelStore::load_from_json(&json).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_reports_issues() {
+        let json = format!(
+            r#"{{
+                "{b}": "Binance",
+                "{lower}": "Binance again",
+                "0x71660c4005ba85c37ccec55d0c4493e66fe775d3": "Coinbase",
+                "0xnope": "Broken",
+                "base:0x71660c4005BA85c37ccec55d0C4493E66Fe775d3": {{ "label": "" }},
+                "arbitrum:0x71660c4005BA85c37ccec55d0C4493E66Fe775d3": {{ "label": "OK", "confidence": 2 }}
+            }}"#,
+            b = BINANCE,
+            lower = BINANCE.to_lowercase()
+        );
+        let parsed = read_json(&json).unwrap();
+        assert_eq!(parsed.foreign, None);
+        let kinds: Vec<IssueKind> = parsed.issues.iter().map(|i| i.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                IssueKind::Checksum,
+                IssueKind::Duplicate,
+                IssueKind::Checksum,
+                IssueKind::Invalid,
+                IssueKind::Invalid,
+                IssueKind::Invalid,
+            ]
+        );
+        assert_eq!(parsed.entries.len(), 3);
+
+        let store = parsed.into_store("test", false).unwrap();
+        let binance = parse_address(BINANCE).unwrap();
+        assert_eq!(store.get(Chain::Ethereum, &binance).as_deref(), Some("Binance again"));
+    }
+}
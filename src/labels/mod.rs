@@ -0,0 +1,586 @@
+mod command;
+mod format;
+
+pub use command::run;
+pub use format::LabelFormat;
+
+use crate::config::LabelsConfig;
+use crate::types::Chain;
+use format::LabelEntry;
+
+use alloy::primitives::Address;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Kind of entity behind a labeled address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelCategory {
+    /// Centralized exchange
+    Cex,
+    /// DeFi protocol contract
+    Defi,
+    Bridge,
+    MarketMaker,
+    /// Stablecoin issuer
+    Issuer,
+    Fund,
+}
+
+impl LabelCategory {
+    /// Get the snake_case name used in label files and JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LabelCategory::Cex => "cex",
+            LabelCategory::Defi => "defi",
+            LabelCategory::Bridge => "bridge",
+            LabelCategory::MarketMaker => "market_maker",
+            LabelCategory::Issuer => "issuer",
+            LabelCategory::Fund => "fund",
+        }
+    }
+
+    /// Get the display name of the category
+    pub fn name(&self) -> &'static str {
+        match self {
+            LabelCategory::Cex => "CEX",
+            LabelCategory::Defi => "DeFi",
+            LabelCategory::Bridge => "Bridge",
+            LabelCategory::MarketMaker => "Market Maker",
+            LabelCategory::Issuer => "Issuer",
+            LabelCategory::Fund => "Fund",
+        }
+    }
+}
+
+impl fmt::Display for LabelCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Everything known about a labeled address
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LabelRecord {
+    /// Human-readable name, e.g. `Binance Hot Wallet 14`
+    pub label: String,
+    /// Organization behind the address, e.g. `Binance`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<LabelCategory>,
+    /// Where the label came from, e.g. `etherscan`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Confidence between 0 and 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+}
+
+impl LabelRecord {
+    /// Create a record with only a name
+    pub fn new(label: String) -> Self {
+        Self {
+            label,
+            entity: None,
+            category: None,
+            source: None,
+            confidence: None,
+        }
+    }
+}
+
+/// Address label store for mapping addresses to human-readable names
+///
+/// Labels are either global or scoped to one chain. A chain-scoped label
+/// takes precedence over the global label for the same address, and a
+/// chain-scoped tombstone hides the global label on that chain.
+#[derive(Debug, Clone)]
+pub struct LabelStore {
+    labels: HashMap<(Option<Chain>, Address), LabelRecord>,
+    /// Addresses whose global label does not apply on one chain
+    hidden: HashSet<(Chain, Address)>,
+}
+
+impl LabelStore {
+    /// Create an empty label store
+    pub fn new() -> Self {
+        Self {
+            labels: HashMap::new(),
+            hidden: HashSet::new(),
+        }
+    }
+
+    /// Build a store from entries, later entries replacing earlier ones
+    pub fn from_entries(entries: Vec<LabelEntry>) -> Self {
+        Self {
+            labels: entries.into_iter().collect(),
+            hidden: HashSet::new(),
+        }
+    }
+
+    /// Load labels from a file in the given format
+    ///
+    /// Invalid and duplicate entries are logged and skipped, or fail the
+    /// load in strict mode.
+    pub fn load_from_path(path: &Path, format: LabelFormat, strict: bool) -> eyre::Result<Self> {
+        format::read(path, format)?.into_store(&path.display().to_string(), strict)
+    }
+
+    /// Load labels from JSON string
+    ///
+    /// Keys are an address, optionally prefixed with a chain to scope the
+    /// label (`base:0x…`). Each value is either a bare name, a
+    /// [`LabelRecord`] object, or `null` on a chain-scoped key to hide the
+    /// global label on that chain. Public dataset layouts are also accepted, see
+    /// [`format::read_json`].
+    pub fn load_from_json(json: &str) -> eyre::Result<Self> {
+        format::read_json(json)?.into_store("JSON", false)
+    }
+
+    /// Load and merge every configured label source
+    ///
+    /// Missing standard files are skipped; files listed in the config must
+    /// exist and parse. In strict mode any invalid entry is an error.
+    pub fn load_layered(config: &LabelsConfig) -> eyre::Result<LayeredLabels> {
+        let mut layers = Vec::new();
+
+        if config.embedded {
+            layers.push((
+                "embedded".to_string(),
+                Self::load_from_json(include_str!("../../data/labels.json"))?,
+            ));
+        }
+        for path in standard_files() {
+            match Self::load_from_path(&path, LabelFormat::from_path(&path), config.strict) {
+                Ok(store) => layers.push((path.display().to_string(), store)),
+                Err(e) if config.strict => return Err(e),
+                Err(e) => tracing::warn!("Skipping labels in {}: {}", path.display(), e),
+            }
+        }
+        for path in &config.files {
+            let store = Self::load_from_path(path, LabelFormat::from_path(path), config.strict)
+                .map_err(|e| eyre::eyre!("failed to load labels from {}: {}", path.display(), e))?;
+            layers.push((path.display().to_string(), store));
+        }
+
+        Ok(Self::merge(layers))
+    }
+
+    /// Merge layers in order of increasing precedence, recording overridden labels
+    ///
+    /// A global label overrides chain-scoped labels for the same address from
+    /// earlier layers too, since lookups would otherwise prefer them. Scoped
+    /// and global labels from the same layer are kept side by side. Tombstones
+    /// behave like chain-scoped labels: they replace earlier labels for their
+    /// chain and give way to later global labels.
+    pub fn merge(layers: Vec<(String, LabelStore)>) -> LayeredLabels {
+        let mut store = Self::new();
+        // Layer index and source of every merged label
+        let mut origin: HashMap<(Option<Chain>, Address), (usize, String)> = HashMap::new();
+        // Layer index of every merged tombstone
+        let mut hidden_origin: HashMap<(Chain, Address), usize> = HashMap::new();
+        let mut conflicts = Vec::new();
+        let mut sources = Vec::new();
+
+        for (index, (source, layer)) in layers.into_iter().enumerate() {
+            sources.push((source.clone(), layer.len()));
+            for (key, record) in layer.labels {
+                let mut overridden = vec![key];
+                if key.0.is_none() {
+                    overridden.extend(Chain::ALL.map(|chain| (Some(chain), key.1)));
+                    for chain in Chain::ALL {
+                        if hidden_origin.get(&(chain, key.1)).is_some_and(|i| *i != index) {
+                            hidden_origin.remove(&(chain, key.1));
+                            store.hidden.remove(&(chain, key.1));
+                        }
+                    }
+                }
+                for old_key in overridden {
+                    let Some((old_index, old_source)) = origin.get(&old_key) else {
+                        continue;
+                    };
+                    if old_key != key && *old_index == index {
+                        continue;
+                    }
+                    let previous = &store.labels[&old_key];
+                    if previous.label != record.label || previous.category != record.category {
+                        conflicts.push(LabelConflict {
+                            chain: old_key.0,
+                            address: key.1,
+                            overridden_source: old_source.clone(),
+                            overridden: previous.label.clone(),
+                            source: source.clone(),
+                            label: record.label.clone(),
+                        });
+                    }
+                    if old_key != key {
+                        store.labels.remove(&old_key);
+                        origin.remove(&old_key);
+                    }
+                }
+                origin.insert(key, (index, source.clone()));
+                store.labels.insert(key, record);
+            }
+            for (chain, address) in layer.hidden {
+                let key = (Some(chain), address);
+                if origin.get(&key).is_some_and(|(i, _)| *i != index) {
+                    origin.remove(&key);
+                    store.labels.remove(&key);
+                }
+                hidden_origin.insert((chain, address), index);
+                store.hidden.insert((chain, address));
+            }
+        }
+
+        LayeredLabels {
+            store,
+            sources,
+            conflicts,
+        }
+    }
+
+    /// Get the label for an address on a chain
+    pub fn get(&self, chain: Chain, address: &Address) -> Option<String> {
+        self.record(chain, address).map(|record| record.label.clone())
+    }
+
+    /// Get the full record for an address on a chain, falling back to its global label
+    /// unless a tombstone hides it there
+    pub fn record(&self, chain: Chain, address: &Address) -> Option<&LabelRecord> {
+        if let Some(record) = self.labels.get(&(Some(chain), *address)) {
+            return Some(record);
+        }
+        if self.hides(chain, address) {
+            return None;
+        }
+        self.labels.get(&(None, *address))
+    }
+
+    /// Whether a tombstone hides the global label of an address on a chain
+    pub fn hides(&self, chain: Chain, address: &Address) -> bool {
+        self.hidden.contains(&(chain, *address))
+    }
+
+    /// Get the category of an address on a chain
+    pub fn category(&self, chain: Chain, address: &Address) -> Option<LabelCategory> {
+        self.record(chain, address).and_then(|record| record.category)
+    }
+
+    /// Check if an address has a label on a chain
+    #[allow(dead_code)]
+    pub fn has_label(&self, chain: Chain, address: &Address) -> bool {
+        self.record(chain, address).is_some()
+    }
+
+    /// Get the total number of labels
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Check if the store is empty
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// All labels sorted by chain scope (global first) and address
+    pub fn sorted(&self) -> Vec<(&(Option<Chain>, Address), &LabelRecord)> {
+        let mut entries: Vec<_> = self.labels.iter().collect();
+        entries.sort_by_key(|(key, _)| **key);
+        entries
+    }
+
+    /// Add a label for an address, on one chain or globally, returning the one it replaces
+    pub fn insert(
+        &mut self,
+        chain: Option<Chain>,
+        address: Address,
+        record: LabelRecord,
+    ) -> Option<LabelRecord> {
+        self.labels.insert((chain, address), record)
+    }
+
+    /// Remove the label for an address, on one chain or globally
+    pub fn remove(&mut self, chain: Option<Chain>, address: &Address) -> Option<LabelRecord> {
+        self.labels.remove(&(chain, *address))
+    }
+}
+
+/// Result of merging several label sources
+#[derive(Debug)]
+pub struct LayeredLabels {
+    pub store: LabelStore,
+    /// Each source that was loaded, with its label count, lowest precedence first
+    pub sources: Vec<(String, usize)>,
+    pub conflicts: Vec<LabelConflict>,
+}
+
+/// An address labeled differently by two sources
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelConflict {
+    pub chain: Option<Chain>,
+    pub address: Address,
+    /// Source and label that lost
+    pub overridden_source: String,
+    pub overridden: String,
+    /// Source and label that won
+    pub source: String,
+    pub label: String,
+}
+
+impl fmt::Display for LabelConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(chain) = self.chain {
+            write!(f, "{}:", chain.name().to_lowercase())?;
+        }
+        write!(
+            f,
+            "{:?}: {:?} from {} overrides {:?} from {}",
+            self.address, self.label, self.source, self.overridden, self.overridden_source
+        )
+    }
+}
+
+/// System-wide label file
+const SYSTEM_PATH: &str = "/etc/usdc_whale_detector/labels.json";
+
+/// Project-local label files, the first one found is used
+const LOCAL_PATHS: [&str; 3] = [
+    "data/labels.json",
+    "./data/labels.json",
+    "../data/labels.json",
+];
+
+/// Standard label files that exist, in order of increasing precedence
+pub fn standard_files() -> Vec<PathBuf> {
+    let local = LOCAL_PATHS.iter().map(PathBuf::from).find(|p| p.exists());
+    [Some(PathBuf::from(SYSTEM_PATH)), user_path(), local]
+        .into_iter()
+        .flatten()
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Per-user label file under the XDG config directory
+fn user_path() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_dir.join("usdc_whale_detector").join("labels.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_load_from_json() {
+        let json = r#"{
+            "0x28C6c06298d514Db089934071355E5743bf21d60": "Binance",
+            "0x71660c4005BA85c37ccec55d0C4493E66Fe775d3": "Coinbase"
+        }"#;
+
+        let store = LabelStore::load_from_json(json).unwrap();
+        assert_eq!(store.len(), 2);
+
+        let binance_addr =
+            Address::from_str("0x28C6c06298d514Db089934071355E5743bf21d60").unwrap();
+        assert_eq!(
+            store.get(Chain::Ethereum, &binance_addr),
+            Some("Binance".to_string())
+        );
+    }
+
+    #[test]
+    fn test_load_records() {
+        let json = r#"{
+            "0x28C6c06298d514Db089934071355E5743bf21d60": {
+                "label": "Binance Hot Wallet 14",
+                "entity": "Binance",
+                "category": "cex",
+                "source": "etherscan",
+                "confidence": 0.9
+            },
+            "0x55FE002aefF02F77364de339a1292923A15844B8": "Circle",
+            "0x71660c4005BA85c37ccec55d0C4493E66Fe775d3": { "label": "Bad", "category": "casino" }
+        }"#;
+
+        let store = LabelStore::load_from_json(json).unwrap();
+        assert_eq!(store.len(), 2);
+
+        let binance = Address::from_str("0x28C6c06298d514Db089934071355E5743bf21d60").unwrap();
+        let record = store.record(Chain::Ethereum, &binance).unwrap();
+        assert_eq!(record.entity.as_deref(), Some("Binance"));
+        assert_eq!(record.confidence, Some(0.9));
+        assert_eq!(store.category(Chain::Base, &binance), Some(LabelCategory::Cex));
+        assert_eq!(
+            store.get(Chain::Ethereum, &binance),
+            Some("Binance Hot Wallet 14".to_string())
+        );
+
+        let circle = Address::from_str("0x55FE002aefF02F77364de339a1292923A15844B8").unwrap();
+        assert_eq!(store.category(Chain::Ethereum, &circle), None);
+    }
+
+    #[test]
+    fn test_chain_scoped_labels() {
+        let json = r#"{
+            "0x4Dbd4fc535Ac27206064B68FfCf827b0A60BAB3f": "Global",
+            "arbitrum:0x4Dbd4fc535Ac27206064B68FfCf827b0A60BAB3f": "Arbitrum only",
+            "base:0xcEe284F754E854890e311e3280b767F80797180d": "Base only",
+            "solana:0xcEe284F754E854890e311e3280b767F80797180d": "Unknown chain"
+        }"#;
+
+        let store = LabelStore::load_from_json(json).unwrap();
+        assert_eq!(store.len(), 3);
+
+        let shared = Address::from_str("0x4Dbd4fc535Ac27206064B68FfCf827b0A60BAB3f").unwrap();
+        assert_eq!(store.get(Chain::Arbitrum, &shared).as_deref(), Some("Arbitrum only"));
+        assert_eq!(store.get(Chain::Ethereum, &shared).as_deref(), Some("Global"));
+
+        let scoped = Address::from_str("0xcEe284F754E854890e311e3280b767F80797180d").unwrap();
+        assert_eq!(store.get(Chain::Base, &scoped).as_deref(), Some("Base only"));
+        assert!(!store.has_label(Chain::Ethereum, &scoped));
+    }
+
+    #[test]
+    fn test_scoped_tombstones_hide_global_labels() {
+        let json = r#"{
+            "0x4Dbd4fc535Ac27206064B68FfCf827b0A60BAB3f": "Bridge",
+            "base:0x4Dbd4fc535Ac27206064B68FfCf827b0A60BAB3f": null,
+            "0xcEe284F754E854890e311e3280b767F80797180d": null
+        }"#;
+        let parsed = format::read_json(json).unwrap();
+        assert_eq!(parsed.issues.len(), 1);
+        assert_eq!(parsed.issues[0].kind, format::IssueKind::Invalid);
+
+        let store = parsed.store();
+        let address = Address::from_str("0x4Dbd4fc535Ac27206064B68FfCf827b0A60BAB3f").unwrap();
+        assert_eq!(store.get(Chain::Base, &address), None);
+        assert_eq!(store.get(Chain::Ethereum, &address).as_deref(), Some("Bridge"));
+
+        // Tombstones survive a rewrite in either format
+        let mut json = Vec::new();
+        format::write_json(&store, &mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.contains("\"base:0x4Dbd4fc535Ac27206064B68FfCf827b0A60BAB3f\": null"), "{}", json);
+        let mut csv = Vec::new();
+        format::write_csv(&store, &mut csv).unwrap();
+        let reread = format::read_csv(csv.as_slice()).unwrap();
+        assert!(reread.issues.is_empty());
+        assert!(reread.store().hides(Chain::Base, &address));
+
+        // A later tombstone hides an earlier global label, a later global label revives it
+        let global = LabelStore::load_from_json(
+            r#"{ "0x4Dbd4fc535Ac27206064B68FfCf827b0A60BAB3f": "Bridge" }"#,
+        )
+        .unwrap();
+        let tombstone = LabelStore::load_from_json(
+            r#"{ "base:0x4Dbd4fc535Ac27206064B68FfCf827b0A60BAB3f": null }"#,
+        )
+        .unwrap();
+        let merged = LabelStore::merge(vec![
+            ("embedded".to_string(), global.clone()),
+            ("user".to_string(), tombstone.clone()),
+        ]);
+        assert_eq!(merged.store.get(Chain::Base, &address), None);
+        assert_eq!(merged.store.get(Chain::Arbitrum, &address).as_deref(), Some("Bridge"));
+
+        let merged = LabelStore::merge(vec![
+            ("embedded".to_string(), tombstone),
+            ("user".to_string(), global),
+        ]);
+        assert_eq!(merged.store.get(Chain::Base, &address).as_deref(), Some("Bridge"));
+    }
+
+    #[test]
+    fn test_merge_reports_conflicts() {
+        let embedded = LabelStore::load_from_json(
+            r#"{
+                "0x28C6c06298d514Db089934071355E5743bf21d60": "Binance",
+                "0x71660c4005BA85c37ccec55d0C4493E66Fe775d3": "Coinbase"
+            }"#,
+        )
+        .unwrap();
+        let user = LabelStore::load_from_json(
+            r#"{
+                "0x28C6c06298d514Db089934071355E5743bf21d60": "Binance 14",
+                "0x71660c4005BA85c37ccec55d0C4493E66Fe775d3": "Coinbase",
+                "base:0x71660c4005BA85c37ccec55d0C4493E66Fe775d3": "Coinbase Base"
+            }"#,
+        )
+        .unwrap();
+
+        let merged = LabelStore::merge(vec![
+            ("embedded".to_string(), embedded),
+            ("user".to_string(), user),
+        ]);
+        assert_eq!(merged.store.len(), 3);
+        assert_eq!(merged.sources, vec![("embedded".to_string(), 2), ("user".to_string(), 3)]);
+
+        // Identical labels and new chain-scoped labels are not conflicts
+        assert_eq!(merged.conflicts.len(), 1);
+        let conflict = &merged.conflicts[0];
+        assert_eq!(conflict.overridden, "Binance");
+        assert_eq!(conflict.overridden_source, "embedded");
+        assert_eq!(conflict.label, "Binance 14");
+
+        let binance = conflict.address;
+        assert_eq!(
+            merged.store.get(Chain::Ethereum, &binance).as_deref(),
+            Some("Binance 14")
+        );
+    }
+
+    #[test]
+    fn test_global_override_beats_earlier_scoped_label() {
+        let embedded = LabelStore::load_from_json(
+            r#"{
+                "ethereum:0x4DBD4fc535Ac27206064B68FfCf827b0A60BAB3f": "Arbitrum Bridge",
+                "0x4DBD4fc535Ac27206064B68FfCf827b0A60BAB3f": "Bridge"
+            }"#,
+        )
+        .unwrap();
+        let user = LabelStore::load_from_json(
+            r#"{ "0x4DBD4fc535Ac27206064B68FfCf827b0A60BAB3f": "My Bridge" }"#,
+        )
+        .unwrap();
+
+        let merged = LabelStore::merge(vec![
+            ("embedded".to_string(), embedded),
+            ("user".to_string(), user),
+        ]);
+        let address = merged.conflicts[0].address;
+        assert_eq!(
+            merged.store.get(Chain::Ethereum, &address).as_deref(),
+            Some("My Bridge")
+        );
+        assert_eq!(merged.store.len(), 1);
+
+        let mut conflicts: Vec<_> = merged
+            .conflicts
+            .iter()
+            .map(|c| (c.chain, c.overridden.as_str()))
+            .collect();
+        conflicts.sort();
+        assert_eq!(
+            conflicts,
+            vec![(None, "Bridge"), (Some(Chain::Ethereum), "Arbitrum Bridge")]
+        );
+    }
+
+    #[test]
+    fn test_embedded_defaults_are_clean() {
+        let parsed = format::read_json(include_str!("../../data/labels.json")).unwrap();
+        assert_eq!(parsed.issues, Vec::new());
+
+        let store = parsed.into_store("embedded", true).unwrap();
+        assert!(!store.is_empty());
+        assert!(store.labels.values().all(|record| record.category.is_some()));
+    }
+}
+
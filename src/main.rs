@@ -1,82 +1,66 @@
+#[cfg(feature = "api")]
+mod api;
+mod classify;
+mod cli;
 mod config;
+mod console;
+mod ens;
+mod export;
+mod filter;
+mod fx;
+mod i18n;
+mod jsonl;
 mod labels;
+mod metrics;
 mod monitor;
+mod notify;
+#[cfg(any(feature = "nats", feature = "kafka", feature = "redis"))]
+mod publish;
+mod query;
+mod reload;
+mod schema;
+mod status;
+mod store;
+mod template;
+#[cfg(feature = "tui")]
+mod tui;
 mod types;
 
-use crate::config::{get_all_chains, WHALE_THRESHOLD_USD};
+use crate::classify::AddressClassifier;
+use crate::cli::{Cli, Command, OutputMode};
+use crate::config::{get_all_chains, AppConfig};
+use crate::console::Console;
+use crate::ens::EnsNames;
+use crate::fx::FxRates;
+use crate::i18n::{fill, Lang};
+use crate::jsonl::JsonlWriter;
 use crate::labels::LabelStore;
+use crate::metrics::Metrics;
 use crate::monitor::ChainMonitor;
-use crate::types::WhaleTransfer;
+use crate::notify::{Dispatcher, Notifier};
+use crate::reload::{Live, Reloader};
+use crate::status::StatusRegistry;
+use crate::store::SqliteStore;
+use crate::template::{Templates, CONSOLE};
+use crate::types::{format_fiat, WhaleTransfer};
 
-use chrono::Local;
+use clap::Parser;
 use colored::Colorize;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-
-/// Print a whale transfer to the console with formatting
-fn print_whale_transfer(transfer: &WhaleTransfer) {
-    let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
-    let chain_color = match transfer.chain {
-        types::Chain::Ethereum => "blue",
-        types::Chain::Arbitrum => "cyan",
-        types::Chain::Base => "magenta",
-    };
-
-    println!();
-    println!(
-        "{} {} 🐋 {}",
-        format!("[{}]", timestamp).bright_black(),
-        format!("[{}]", transfer.chain.name()).color(chain_color).bold(),
-        "WHALE TRANSFER DETECTED".bright_yellow().bold()
-    );
-    println!(
-        "  {} {}",
-        "Amount:".bright_white(),
-        transfer.formatted_amount().bright_green().bold()
-    );
-    println!(
-        "  {} {}",
-        "From:  ".bright_white(),
-        transfer.formatted_from()
-    );
-    println!(
-        "  {} {}",
-        "To:    ".bright_white(),
-        transfer.formatted_to()
-    );
-    println!(
-        "  {} {}",
-        "Tx:    ".bright_white(),
-        transfer.short_tx_hash().bright_blue()
-    );
-    println!(
-        "  {} {}",
-        "Block: ".bright_white(),
-        transfer.block_number.to_string().bright_black()
-    );
-    println!(
-        "  {} {}",
-        "Link:  ".bright_white(),
-        transfer.chain.explorer_tx_url(&transfer.tx_hash).bright_blue().underline()
-    );
-}
-
-/// Print startup banner
-fn print_banner() {
-    println!();
-    println!("{}", "╔═══════════════════════════════════════════════════════════════╗".bright_cyan());
-    println!("{}", "║                                                               ║".bright_cyan());
-    println!("{}", "║   🐋  USDC WHALE DETECTOR  🐋                                 ║".bright_cyan());
-    println!("{}", "║                                                               ║".bright_cyan());
-    println!("{}", "║   Monitoring large USDC transfers across chains               ║".bright_cyan());
-    println!("{}", "║                                                               ║".bright_cyan());
-    println!("{}", "╚═══════════════════════════════════════════════════════════════╝".bright_cyan());
-    println!();
-}
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
-    // Initialize tracing
+    let cli = Cli::parse();
+
+    // Initialize tracing (on stderr so stdout stays machine-readable, and
+    // nowhere while the dashboard owns the terminal)
+    let log_writer = if cli.output == OutputMode::Tui {
+        BoxMakeWriter::new(std::io::sink)
+    } else {
+        BoxMakeWriter::new(std::io::stderr)
+    };
     tracing_subscriber::fmt()
         .with_env_filter(
             tracing_subscriber::EnvFilter::from_default_env()
@@ -84,56 +68,240 @@ async fn main() -> eyre::Result<()> {
                 .add_directive("alloy=warn".parse()?),
         )
         .with_target(false)
+        .with_writer(log_writer)
         .init();
 
-    print_banner();
+    let app_config = AppConfig::load(cli.config.as_deref())?;
+    i18n::init(
+        cli.lang
+            .or(app_config.language)
+            .or_else(Lang::from_env)
+            .unwrap_or_default(),
+    );
+
+    match cli.command {
+        Some(Command::Query(args)) => query::run(args, &app_config),
+        Some(Command::Export(args)) => export::run(args, &app_config),
+        Some(Command::Labels(args)) => labels::run(args, &app_config),
+        None => run_monitor(cli, app_config).await,
+    }
+}
+
+/// Run the chain monitors until they exit
+async fn run_monitor(cli: Cli, app_config: AppConfig) -> eyre::Result<()> {
+    let messages = i18n::messages();
+
+    #[cfg(not(feature = "tui"))]
+    if cli.output == OutputMode::Tui {
+        eyre::bail!("--output tui requires the `tui` feature");
+    }
+
+    // In JSON Lines mode on stdout, nothing but records may go to stdout,
+    // and the dashboard draws the whole screen itself
+    let quiet = (cli.output == OutputMode::Jsonl && cli.output_file.is_none())
+        || cli.output == OutputMode::Tui;
+    console::init_colors(cli.color, cli.output);
+
+    let templates = Arc::new(Templates::new(&app_config.templates)?);
+    let console_templates = Arc::new(Templates::console(&app_config.templates)?);
+    let console = if console_templates.contains(CONSOLE) {
+        Some(console_templates.get(CONSOLE)?)
+    } else {
+        None
+    };
+    // Get chain configurations
+    let chains = get_all_chains();
+    let threshold_usd = app_config.threshold_usd();
+
+    if !quiet {
+        match console_templates.banner(&chains.iter().map(|c| c.chain).collect::<Vec<_>>(), threshold_usd)? {
+            Some(banner) => println!("{}", banner),
+            None if cli.output == OutputMode::Pretty => console::print_banner(),
+            None => println!("USDC Whale Detector v{}", env!("CARGO_PKG_VERSION")),
+        }
+    }
+
+    let mut jsonl = match (cli.output, &cli.output_file) {
+        (OutputMode::Jsonl, Some(path)) => Some(JsonlWriter::file(
+            path,
+            cli.rotate_size_mb * 1024 * 1024,
+            cli.rotate_keep,
+        )?),
+        (OutputMode::Jsonl, None) => Some(JsonlWriter::stdout()),
+        _ => None,
+    };
 
     // Load address labels
-    let labels = Arc::new(LabelStore::default());
-    println!(
-        "{} {} address labels",
-        "✓".bright_green(),
-        format!("Loaded {}", labels.len()).bright_white()
-    );
+    let layered = LabelStore::load_layered(&app_config.labels)?;
+    for (source, count) in &layered.sources {
+        tracing::info!("Loaded {} address labels from {}", count, source);
+    }
+    for conflict in &layered.conflicts {
+        tracing::warn!("Label conflict: {}", conflict);
+    }
+    if layered.store.is_empty() {
+        tracing::warn!("No address labels loaded, all addresses will show as unknown");
+    }
+    let label_count = layered.store.len();
+    let live = Live::new(layered.store, threshold_usd);
+    let classifier = AddressClassifier::open(&app_config.classify)?;
+    tracing::debug!("Loaded {} cached address classifications", classifier.len());
+    let ens = EnsNames::new(&app_config.ens);
+    // Load exchange rates for fiat display
+    let fx = match &app_config.fx {
+        Some(fx_config) => FxRates::start(fx_config).await?,
+        None => FxRates::disabled(),
+    };
 
-    // Print configuration
-    println!(
-        "{} {} ${} USDC (~100M KRW)",
-        "✓".bright_green(),
-        "Whale threshold:".bright_white(),
-        WHALE_THRESHOLD_USD.to_string().bright_yellow()
-    );
+    if !quiet {
+        println!(
+            "{} {}",
+            "✓".bright_green(),
+            fill(messages.labels_loaded, &[&label_count]).bright_white()
+        );
+
+        // Print configuration
+        let threshold_fiat = fx.convert(threshold_usd as f64);
+        println!(
+            "{} {} ${} USDC{}",
+            "✓".bright_green(),
+            messages.whale_threshold.bright_white(),
+            threshold_usd.to_string().bright_yellow(),
+            if threshold_fiat.is_empty() {
+                String::new()
+            } else {
+                format!(" (≈ {})", format_fiat(&threshold_fiat))
+            }
+        );
+    }
+
+    // Start notification delivery and history storage
+    let notifiers = notify::build_notifiers(&app_config.notify, &templates)?;
+    let mut sinks = notify::build_webhooks(&app_config.notify, &templates)?;
+    let notifier_count = notifiers.len() + sinks.len();
+    let history = match &app_config.history.sqlite_path {
+        Some(path) => Some((path, SqliteStore::open(path)?)),
+        None => None,
+    };
+    if let Some((path, store)) = &history {
+        if !quiet {
+            println!(
+                "{} {}",
+                "✓".bright_green(),
+                fill(messages.history_database, &[&path.display(), &store.count()?])
+                    .bright_white()
+            );
+        }
+        sinks.push(Arc::new(store.clone()) as Arc<dyn Notifier>);
+    }
+    #[cfg(feature = "postgres")]
+    if let Some(postgres) = &app_config.history.postgres {
+        sinks.push(Arc::new(store::PostgresSink::connect(postgres).await?) as Arc<dyn Notifier>);
+    }
+    #[cfg(not(feature = "postgres"))]
+    if app_config.history.postgres.is_some() {
+        eyre::bail!("history.postgres is configured but this build lacks the `postgres` feature");
+    }
+    #[cfg(any(feature = "nats", feature = "kafka", feature = "redis"))]
+    sinks.extend(publish::build_publishers(&app_config.publish)?);
+    #[cfg(not(any(feature = "nats", feature = "kafka", feature = "redis")))]
+    if !app_config.publish.is_empty() {
+        eyre::bail!("[publish] is configured but this build lacks the `nats`, `kafka` and `redis` features");
+    }
+    let status = Arc::new(StatusRegistry::new());
+    let metrics = Arc::new(Metrics::new()?);
+    if let Some(metrics_config) = app_config.metrics.clone() {
+        let metrics = Arc::clone(&metrics);
+        tokio::spawn(async move {
+            if let Err(e) = metrics::serve(&metrics_config.listen, metrics).await {
+                tracing::error!(error = %e, "Metrics endpoint failed");
+            }
+        });
+    }
+
+    #[cfg(feature = "api")]
+    if let Some(api_config) = app_config.api.clone() {
+        let recent = Arc::new(api::RecentTransfers::new(
+            api_config.recent_capacity,
+            api::stream_channel(api_config.stream_buffer),
+        ));
+        sinks.push(Arc::clone(&recent) as Arc<dyn Notifier>);
+        let state = api::ApiState {
+            recent,
+            status: Arc::clone(&status),
+            store: history.map(|(_, store)| store),
+            started: std::time::Instant::now(),
+        };
+        tokio::spawn(async move {
+            if let Err(e) = api::serve(&api_config, state).await {
+                tracing::error!(error = %e, "REST API failed");
+            }
+        });
+    }
+    #[cfg(not(feature = "api"))]
+    if app_config.api.is_some() {
+        eyre::bail!("[api] is configured but this build lacks the `api` feature");
+    }
+
+    let dispatcher = Arc::new(Dispatcher::spawn(sinks, notifiers));
+    Reloader::new(
+        AppConfig::resolve_path(cli.config.as_deref()),
+        app_config.clone(),
+        Arc::clone(&live),
+        Arc::clone(&dispatcher),
+    )?
+    .spawn()?;
+    if !quiet && notifier_count > 0 {
+        println!(
+            "{} {}",
+            "✓".bright_green(),
+            fill(messages.notification_channels, &[&notifier_count]).bright_white()
+        );
+    }
 
     // Create channel for whale transfers
     let (tx, mut rx) = mpsc::channel::<WhaleTransfer>(100);
 
-    // Get chain configurations
-    let chains = get_all_chains();
-    println!(
-        "{} {} chains: {}",
-        "✓".bright_green(),
-        "Monitoring".bright_white(),
-        chains
+    if !quiet {
+        let chain_names = chains
             .iter()
             .map(|c| c.chain.name())
             .collect::<Vec<_>>()
-            .join(", ")
-            .bright_cyan()
-    );
+            .join(", ");
+        println!(
+            "{} {}",
+            "✓".bright_green(),
+            fill(messages.monitoring_chains, &[&chain_names.bright_cyan()])
+        );
 
-    println!();
-    println!("{}", "Starting monitors...".bright_white());
-    println!("{}", "─".repeat(65).bright_black());
+        println!();
+        println!("{}", messages.starting_monitors.bright_white());
+        println!("{}", "─".repeat(65).bright_black());
+    }
 
     // Spawn monitors for each chain
     let mut handles = Vec::new();
 
     for chain_config in chains {
-        let labels_clone = Arc::clone(&labels);
+        let live_clone = Arc::clone(&live);
+        let classifier_clone = Arc::clone(&classifier);
+        let ens_clone = Arc::clone(&ens);
         let tx_clone = tx.clone();
+        let status_clone = Arc::clone(&status);
+        let metrics_clone = Arc::clone(&metrics);
+        let fx_clone = Arc::clone(&fx);
 
         let handle = tokio::spawn(async move {
-            let monitor = ChainMonitor::new(chain_config, labels_clone, tx_clone);
+            let monitor = ChainMonitor::new(
+                chain_config,
+                live_clone,
+                tx_clone,
+                status_clone,
+                metrics_clone,
+                fx_clone,
+            )
+            .with_classifier(classifier_clone)
+            .with_ens(ens_clone);
             if let Err(e) = monitor.run().await {
                 tracing::error!(error = %e, "Monitor failed");
             }
@@ -142,13 +310,40 @@ async fn main() -> eyre::Result<()> {
         handles.push(handle);
     }
 
+    metrics::sample_channel_depth(Arc::clone(&metrics), &tx);
+
     // Drop the original sender so the receiver knows when all monitors are done
     drop(tx);
 
+    #[cfg(feature = "tui")]
+    if cli.output == OutputMode::Tui {
+        // Quitting the dashboard ends the process, monitors included
+        return tui::run(rx, dispatcher, status).await;
+    }
+
     // Process whale transfers from all chains
+    let mut printer = Console::new(cli.output);
     let printer_handle = tokio::spawn(async move {
         while let Some(transfer) = rx.recv().await {
-            print_whale_transfer(&transfer);
+            dispatcher.dispatch(&transfer).await;
+
+            match jsonl.as_mut() {
+                Some(writer) => {
+                    if let Err(e) = writer.write(&transfer) {
+                        tracing::error!(error = %e, "Failed to write JSON line");
+                    }
+                }
+                None => match &console {
+                    Some(template) => match template.render(&transfer) {
+                        Ok(text) => println!("{}", text),
+                        Err(e) => {
+                            tracing::error!(error = %e, "Failed to render console template");
+                            printer.print(&transfer);
+                        }
+                    },
+                    None => printer.print(&transfer),
+                },
+            }
         }
     });
 
@@ -160,6 +355,7 @@ async fn main() -> eyre::Result<()> {
 
     // Wait for the printer to finish
     let _ = printer_handle.await;
+    classifier.flush().await;
 
     Ok(())
 }
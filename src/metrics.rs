@@ -0,0 +1,229 @@
+use crate::types::Chain;
+
+use eyre::Result;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry, TextEncoder,
+};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+/// Token label used on per-token metrics
+pub const TOKEN: &str = "USDC";
+
+/// Prometheus metrics describing monitor health
+pub struct Metrics {
+    registry: Registry,
+    /// Latest block reported by the RPC endpoint
+    pub head_block: IntGaugeVec,
+    /// Last block whose logs were scanned
+    pub processed_block: IntGaugeVec,
+    /// Duration of one poll iteration
+    pub poll_duration: HistogramVec,
+    /// Latency of `eth_getLogs` calls
+    pub get_logs_latency: HistogramVec,
+    /// Failed `eth_getLogs` calls
+    pub get_logs_errors: IntCounterVec,
+    /// Transfer logs scanned
+    pub logs_scanned: IntCounterVec,
+    /// Whale transfers detected
+    pub whales_detected: IntCounterVec,
+    /// Transfers waiting in the monitor → output channel
+    pub channel_depth: IntGauge,
+    /// Monitor loop restarts after an error
+    pub restarts: IntCounterVec,
+}
+
+impl Metrics {
+    /// Create and register all metrics
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new_custom(Some("whale".to_string()), None)?;
+
+        let head_block = IntGaugeVec::new(
+            Opts::new("head_block", "Latest block reported by the RPC endpoint"),
+            &["chain"],
+        )?;
+        let processed_block = IntGaugeVec::new(
+            Opts::new("processed_block", "Last block whose logs were scanned"),
+            &["chain"],
+        )?;
+        let poll_duration = HistogramVec::new(
+            HistogramOpts::new("poll_duration_seconds", "Duration of one poll iteration"),
+            &["chain"],
+        )?;
+        let get_logs_latency = HistogramVec::new(
+            HistogramOpts::new("get_logs_duration_seconds", "Latency of scanning a block range"),
+            &["chain", "endpoint"],
+        )?;
+        let get_logs_errors = IntCounterVec::new(
+            Opts::new("get_logs_errors_total", "Block ranges that failed to scan"),
+            &["chain", "endpoint"],
+        )?;
+        let logs_scanned = IntCounterVec::new(
+            Opts::new("logs_scanned_total", "Transfer logs scanned"),
+            &["chain"],
+        )?;
+        let whales_detected = IntCounterVec::new(
+            Opts::new("whales_detected_total", "Whale transfers detected"),
+            &["chain", "token"],
+        )?;
+        let channel_depth = IntGauge::new(
+            "channel_depth",
+            "Transfers waiting between the monitors and the outputs",
+        )?;
+        let restarts = IntCounterVec::new(
+            Opts::new("monitor_restarts_total", "Monitor loop restarts after an error"),
+            &["chain"],
+        )?;
+
+        registry.register(Box::new(head_block.clone()))?;
+        registry.register(Box::new(processed_block.clone()))?;
+        registry.register(Box::new(poll_duration.clone()))?;
+        registry.register(Box::new(get_logs_latency.clone()))?;
+        registry.register(Box::new(get_logs_errors.clone()))?;
+        registry.register(Box::new(logs_scanned.clone()))?;
+        registry.register(Box::new(whales_detected.clone()))?;
+        registry.register(Box::new(channel_depth.clone()))?;
+        registry.register(Box::new(restarts.clone()))?;
+
+        Ok(Self {
+            registry,
+            head_block,
+            processed_block,
+            poll_duration,
+            get_logs_latency,
+            get_logs_errors,
+            logs_scanned,
+            whales_detected,
+            channel_depth,
+            restarts,
+        })
+    }
+
+    /// Render all metrics in the Prometheus text format
+    pub fn render(&self) -> Result<String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+/// Sample the monitor → output channel depth every second
+///
+/// Sampling on a timer rather than on receive means the gauge keeps rising
+/// while the consumer is stuck. Stops once every sender is gone.
+pub fn sample_channel_depth<T: Send + 'static>(metrics: Arc<Metrics>, tx: &mpsc::Sender<T>) {
+    let tx = tx.downgrade();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
+        loop {
+            interval.tick().await;
+            let Some(tx) = tx.upgrade() else { break };
+            metrics
+                .channel_depth
+                .set((tx.max_capacity() - tx.capacity()) as i64);
+        }
+    });
+}
+
+/// Lowercase chain name used as a label value
+pub fn chain_label(chain: Chain) -> String {
+    chain.name().to_lowercase()
+}
+
+/// Host of an RPC URL, used as the `endpoint` label so API keys in paths don't leak
+pub fn endpoint_label(rpc_url: &str) -> String {
+    reqwest::Url::parse(rpc_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Serve `GET /metrics` until the process exits
+///
+/// This is a deliberately minimal HTTP/1.1 responder so that metrics don't
+/// depend on the optional `api` feature.
+pub async fn serve(listen: &str, metrics: Arc<Metrics>) -> Result<()> {
+    let listener = TcpListener::bind(listen).await?;
+    tracing::info!(addr = %listener.local_addr()?, "Metrics endpoint listening");
+
+    loop {
+        let (mut socket, _) = listener.accept().await?;
+        let metrics = Arc::clone(&metrics);
+
+        tokio::spawn(async move {
+            let mut request = [0u8; 1024];
+            let read = match socket.read(&mut request).await {
+                Ok(read) => read,
+                Err(_) => return,
+            };
+            let request = String::from_utf8_lossy(&request[..read]);
+
+            let response = if request.starts_with("GET /metrics") {
+                match metrics.render() {
+                    Ok(body) => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    ),
+                    Err(e) => {
+                        tracing::error!(error = %e, "Failed to render metrics");
+                        "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+                    }
+                }
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+            };
+
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_serves_metrics() {
+        let metrics = Arc::new(Metrics::new().unwrap());
+        metrics
+            .whales_detected
+            .with_label_values(&["base", TOKEN])
+            .inc();
+        metrics.head_block.with_label_values(&["base"]).set(42);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let listen = addr.to_string();
+        let served = Arc::clone(&metrics);
+        tokio::spawn(async move { serve(&listen, served).await });
+
+        let mut body = String::new();
+        for _ in 0..50 {
+            if let Ok(response) = reqwest::get(format!("http://{}/metrics", addr)).await {
+                body = response.text().await.unwrap();
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(body.contains(r#"whale_whales_detected_total{chain="base",token="USDC"} 1"#));
+        assert!(body.contains(r#"whale_head_block{chain="base"} 42"#));
+
+        let (tx, _rx) = mpsc::channel(4);
+        tx.send(()).await.unwrap();
+        tx.send(()).await.unwrap();
+        sample_channel_depth(Arc::clone(&metrics), &tx);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(metrics.channel_depth.get(), 2);
+
+        assert_eq!(
+            endpoint_label("https://eth-mainnet.example.com/v2/SECRET"),
+            "eth-mainnet.example.com"
+        );
+    }
+}
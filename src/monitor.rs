@@ -1,32 +1,69 @@
-use crate::config::{ChainConfig, POLL_INTERVAL_SECS, TRANSFER_EVENT_SIGNATURE, WHALE_THRESHOLD_RAW};
-use crate::labels::LabelStore;
-use crate::types::WhaleTransfer;
+use crate::classify::AddressClassifier;
+use crate::config::{ChainConfig, MAX_BLOCK_RANGE, POLL_INTERVAL_SECS, TRANSFER_EVENT_SIGNATURE};
+use crate::ens::EnsNames;
+use crate::fx::FxRates;
+use crate::metrics::{self, Metrics};
+use crate::reload::Live;
+use crate::status::StatusRegistry;
+use crate::types::{TransferStatus, WhaleTransfer};
 
 use alloy::primitives::{Address, B256, U256};
 use alloy::providers::{Provider, ProviderBuilder};
-use alloy::rpc::types::{Filter, Log};
+use alloy::rpc::types::{BlockNumberOrTag, BlockTransactionsKind, Filter, Log};
+use alloy::transports::Transport;
+use chrono::{DateTime, Utc};
 use eyre::Result;
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tokio::time::sleep;
 
 /// Chain monitor that watches for USDC whale transfers
 pub struct ChainMonitor {
     config: ChainConfig,
-    labels: Arc<LabelStore>,
+    live: Arc<Live>,
+    classifier: Arc<AddressClassifier>,
+    ens: Arc<EnsNames>,
     tx: mpsc::Sender<WhaleTransfer>,
+    status: Arc<StatusRegistry>,
+    metrics: Arc<Metrics>,
+    fx: Arc<FxRates>,
 }
 
 impl ChainMonitor {
     /// Create a new chain monitor
     pub fn new(
         config: ChainConfig,
-        labels: Arc<LabelStore>,
+        live: Arc<Live>,
         tx: mpsc::Sender<WhaleTransfer>,
+        status: Arc<StatusRegistry>,
+        metrics: Arc<Metrics>,
+        fx: Arc<FxRates>,
     ) -> Self {
-        Self { config, labels, tx }
+        Self {
+            config,
+            live,
+            classifier: AddressClassifier::disabled(),
+            ens: EnsNames::disabled(),
+            tx,
+            status,
+            metrics,
+            fx,
+        }
+    }
+
+    /// Classify unlabeled addresses by their code
+    pub fn with_classifier(mut self, classifier: Arc<AddressClassifier>) -> Self {
+        self.classifier = classifier;
+        self
+    }
+
+    /// Look up ENS names of unlabeled addresses
+    pub fn with_ens(mut self, ens: Arc<EnsNames>) -> Self {
+        self.ens = ens;
+        self
     }
 
     /// Start monitoring the chain for whale transfers
@@ -50,6 +87,12 @@ impl ChainMonitor {
                         error = %e,
                         "Monitor error, restarting in 10 seconds..."
                     );
+                    self.status.record_error(self.config.chain, &e);
+                    self.status.update(self.config.chain, |s| s.restarts += 1);
+                    self.metrics
+                        .restarts
+                        .with_label_values(&[&metrics::chain_label(self.config.chain)])
+                        .inc();
                     sleep(Duration::from_secs(10)).await;
                 }
             }
@@ -70,52 +113,162 @@ impl ChainMonitor {
             block = last_block,
             "Starting from block"
         );
+        self.status.update(self.config.chain, |s| {
+            s.head_block = Some(last_block);
+            s.processed_block = Some(last_block);
+        });
 
-        let transfer_topic = B256::from_str(TRANSFER_EVENT_SIGNATURE)?;
+        let chain = metrics::chain_label(self.config.chain);
+        let endpoint = metrics::endpoint_label(&self.config.rpc_url);
+        let head_block = self.metrics.head_block.with_label_values(&[&chain]);
+        let processed_block = self.metrics.processed_block.with_label_values(&[&chain]);
+        let poll_duration = self.metrics.poll_duration.with_label_values(&[&chain]);
+        let get_logs_latency = self
+            .metrics
+            .get_logs_latency
+            .with_label_values(&[&chain, &endpoint]);
+        let get_logs_errors = self
+            .metrics
+            .get_logs_errors
+            .with_label_values(&[&chain, &endpoint]);
+        let logs_scanned = self.metrics.logs_scanned.with_label_values(&[&chain]);
+        let whales_detected = self
+            .metrics
+            .whales_detected
+            .with_label_values(&[&chain, metrics::TOKEN]);
+        head_block.set(last_block as i64);
+        processed_block.set(last_block as i64);
 
         loop {
+            let poll_started = Instant::now();
+
             // Get the latest block
             let latest_block = provider.get_block_number().await?;
+            self.status
+                .update(self.config.chain, |s| s.head_block = Some(latest_block));
+            head_block.set(latest_block as i64);
 
-            if latest_block > last_block {
-                // Query logs for new blocks
-                let filter = Filter::new()
-                    .address(self.config.usdc_address)
-                    .event_signature(transfer_topic)
-                    .from_block(last_block + 1)
-                    .to_block(latest_block);
-
-                match provider.get_logs(&filter).await {
-                    Ok(logs) => {
-                        for log in logs {
-                            if let Some(transfer) = self.process_log(&log) {
-                                if let Err(e) = self.tx.send(transfer).await {
-                                    tracing::error!(
-                                        chain = %self.config.chain,
-                                        error = %e,
-                                        "Failed to send whale transfer"
-                                    );
-                                }
+            while last_block < latest_block {
+                let to_block = latest_block.min(last_block + MAX_BLOCK_RANGE);
+                let get_logs_started = Instant::now();
+                let result = self.fetch_transfers(&provider, last_block + 1, to_block).await;
+                get_logs_latency.observe(get_logs_started.elapsed().as_secs_f64());
+
+                match result {
+                    Ok((scanned, transfers)) => {
+                        logs_scanned.inc_by(scanned as u64);
+                        for mut transfer in transfers {
+                            self.identify(&provider, &mut transfer).await;
+
+                            whales_detected.inc();
+
+                            if let Err(e) = self.tx.send(transfer).await {
+                                tracing::error!(
+                                    chain = %self.config.chain,
+                                    error = %e,
+                                    "Failed to send whale transfer"
+                                );
                             }
                         }
+
+                        // Only advance past a range whose logs and block times were fetched
+                        last_block = to_block;
+                        self.status
+                            .update(self.config.chain, |s| s.processed_block = Some(last_block));
+                        processed_block.set(last_block as i64);
                     }
                     Err(e) => {
                         tracing::warn!(
                             chain = %self.config.chain,
                             error = %e,
-                            "Failed to get logs, will retry"
+                            from_block = last_block + 1,
+                            to_block,
+                            "Failed to scan blocks, will retry the range"
                         );
+                        self.status.record_error(self.config.chain, &e);
+                        get_logs_errors.inc();
+                        break;
                     }
                 }
-
-                last_block = latest_block;
             }
 
+            self.status
+                .update(self.config.chain, |s| s.last_poll = Some(Utc::now()));
+            poll_duration.observe(poll_started.elapsed().as_secs_f64());
+
             // Wait before polling again
             sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
         }
     }
 
+    /// Fetch the whale transfers in a block range, with their block times
+    ///
+    /// Fails as a whole if the logs or any block time can't be fetched, so
+    /// the caller can retry the range without sending anything twice.
+    /// Returns the number of logs scanned along with the transfers.
+    async fn fetch_transfers<T, P>(
+        &self,
+        provider: &P,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<(usize, Vec<WhaleTransfer>)>
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let filter = Filter::new()
+            .address(self.config.usdc_address)
+            .event_signature(B256::from_str(TRANSFER_EVENT_SIGNATURE)?)
+            .from_block(from_block)
+            .to_block(to_block);
+        let logs = provider.get_logs(&filter).await?;
+
+        let mut transfers: Vec<WhaleTransfer> =
+            logs.iter().filter_map(|log| self.process_log(log)).collect();
+        let mut timestamps: HashMap<u64, DateTime<Utc>> = HashMap::new();
+        for transfer in &mut transfers {
+            // Most nodes don't include the block timestamp in logs
+            if transfer.block_timestamp.is_some() {
+                continue;
+            }
+            let block = transfer.block_number;
+            let timestamp = match timestamps.get(&block) {
+                Some(timestamp) => *timestamp,
+                None => {
+                    let header = provider
+                        .get_block_by_number(BlockNumberOrTag::Number(block), BlockTransactionsKind::Hashes)
+                        .await?
+                        .ok_or_else(|| eyre::eyre!("block {} not found", block))?
+                        .header;
+                    let timestamp = DateTime::from_timestamp(header.timestamp as i64, 0)
+                        .ok_or_else(|| eyre::eyre!("block {} has an invalid timestamp", block))?;
+                    timestamps.insert(block, timestamp);
+                    timestamp
+                }
+            };
+            transfer.block_timestamp = Some(timestamp);
+        }
+
+        Ok((logs.len(), transfers))
+    }
+
+    /// Look up the ENS name and account kind of unlabeled addresses
+    async fn identify<T, P>(&self, provider: &P, transfer: &mut WhaleTransfer)
+    where
+        T: Transport + Clone,
+        P: Provider<T>,
+    {
+        let chain = self.config.chain;
+        if transfer.from_label.is_none() {
+            transfer.from_ens = self.ens.lookup(provider, chain, transfer.from).await;
+            transfer.from_kind = self.classifier.classify(provider, chain, transfer.from).await;
+        }
+        if transfer.to_label.is_none() {
+            transfer.to_ens = self.ens.lookup(provider, chain, transfer.to).await;
+            transfer.to_kind = self.classifier.classify(provider, chain, transfer.to).await;
+        }
+    }
+
     /// Process a Transfer event log and return a WhaleTransfer if it meets the threshold
     fn process_log(&self, log: &Log) -> Option<WhaleTransfer> {
         // Transfer event has 3 topics: event signature, from, to
@@ -137,27 +290,139 @@ impl ChainMonitor {
 
         // Check if this is a whale transfer
         let amount_u128 = amount.to::<u128>();
-        if amount_u128 < WHALE_THRESHOLD_RAW {
+        if amount_u128 < self.live.threshold_raw() {
             return None;
         }
 
         // Get transaction hash
         let tx_hash = log.transaction_hash?;
         let block_number = log.block_number?;
+        let status = if log.removed {
+            TransferStatus::Removed
+        } else {
+            TransferStatus::Confirmed
+        };
 
-        // Create whale transfer with labels
+        // Create whale transfer with labels for this chain
+        let chain = self.config.chain;
+        let labels = self.live.labels();
         let transfer = WhaleTransfer::new(
-            self.config.chain,
+            chain,
             tx_hash,
             block_number,
             from,
             to,
             amount,
         )
-        .with_from_label(self.labels.get(&from))
-        .with_to_label(self.labels.get(&to));
+        .with_log_index(log.log_index.unwrap_or_default())
+        .with_block_timestamp(
+            log.block_timestamp
+                .and_then(|t| DateTime::from_timestamp(t as i64, 0)),
+        )
+        .with_status(status)
+        .with_from_label(labels.get(chain, &from))
+        .with_to_label(labels.get(chain, &to))
+        .with_from_category(labels.category(chain, &from))
+        .with_to_category(labels.category(chain, &to));
+        let fiat = self.fx.convert(transfer.amount_usd);
 
-        Some(transfer)
+        Some(transfer.with_fiat(fiat))
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::get_all_chains;
+    use crate::labels::LabelStore;
+    use serde_json::{json, Value};
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    /// Answers `eth_getLogs` with one whale transfer and `eth_getBlockByNumber` with `block`
+    struct MockRpc {
+        block_timestamp: Option<&'static str>,
+        block: Value,
+    }
+
+    impl Respond for MockRpc {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let body: Value = serde_json::from_slice(&request.body).unwrap();
+            let response = match body["method"].as_str().unwrap() {
+                "eth_getLogs" => {
+                    let mut log = json!({
+                        "address": "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                        "topics": [
+                            TRANSFER_EVENT_SIGNATURE,
+                            B256::left_padding_from(&[2; 20]),
+                            B256::left_padding_from(&[3; 20]),
+                        ],
+                        "data": alloy::primitives::hex::encode_prefixed(
+                            U256::from(5_000_000_000_000u64).to_be_bytes::<32>()
+                        ),
+                        "blockNumber": "0x10",
+                        "blockHash": B256::repeat_byte(4),
+                        "transactionHash": B256::repeat_byte(1),
+                        "transactionIndex": "0x0",
+                        "logIndex": "0x1",
+                        "removed": false,
+                    });
+                    if let Some(timestamp) = self.block_timestamp {
+                        log["blockTimestamp"] = json!(timestamp);
+                    }
+                    json!({ "jsonrpc": "2.0", "id": body["id"], "result": [log] })
+                }
+                _ => {
+                    let mut response = json!({ "jsonrpc": "2.0", "id": body["id"] });
+                    match &self.block {
+                        Value::String(message) => {
+                            response["error"] = json!({ "code": -32000, "message": message })
+                        }
+                        block => response["result"] = block.clone(),
+                    }
+                    response
+                }
+            };
+            ResponseTemplate::new(200).set_body_json(response)
+        }
+    }
+
+    async fn fetch(rpc: MockRpc) -> Result<(usize, Vec<WhaleTransfer>)> {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).respond_with(rpc).mount(&server).await;
+        let provider = ProviderBuilder::new().on_http(server.uri().parse().unwrap());
+
+        let (tx, _rx) = mpsc::channel(1);
+        let monitor = ChainMonitor::new(
+            get_all_chains().remove(0),
+            Live::new(LabelStore::new(), 1_000_000),
+            tx,
+            Arc::new(StatusRegistry::new()),
+            Arc::new(Metrics::new().unwrap()),
+            FxRates::disabled(),
+        );
+        monitor.fetch_transfers(&provider, 0x10, 0x10).await
+    }
+
+    #[tokio::test]
+    async fn test_fails_the_range_without_block_times() {
+        let (scanned, transfers) = fetch(MockRpc {
+            block_timestamp: Some("0x6553f100"),
+            block: Value::Null,
+        })
+        .await
+        .unwrap();
+        assert_eq!((scanned, transfers.len()), (1, 1));
+        assert_eq!(transfers[0].block_timestamp.unwrap().timestamp(), 1_700_000_000);
+
+        // A failed or empty block lookup fails the range instead of dropping the time
+        for block in [json!("upstream timeout"), Value::Null] {
+            let rpc = MockRpc {
+                block_timestamp: None,
+                block,
+            };
+            assert!(fetch(rpc).await.is_err());
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use super::{http, Notifier};
+use crate::config::WebhookChannelConfig;
+use crate::filter::TransferFilter;
+use crate::i18n::{self, fill};
+use crate::template::Template;
+use crate::types::{short_address, Chain, WhaleTransfer};
+
+use alloy::primitives::Address;
+use async_trait::async_trait;
+use chrono::Utc;
+use eyre::Result;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+/// Posts whale alerts to a Discord incoming webhook as an embed
+pub struct DiscordNotifier {
+    client: Client,
+    config: WebhookChannelConfig,
+    template: Option<Template>,
+}
+
+impl DiscordNotifier {
+    /// Create a new Discord notifier
+    pub fn new(client: Client, config: WebhookChannelConfig) -> Self {
+        Self {
+            client,
+            config,
+            template: None,
+        }
+    }
+
+    /// Send a user-defined template as the message content instead of an embed
+    pub fn with_template(mut self, template: Option<Template>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Build the embed message for a transfer
+    pub fn render(transfer: &WhaleTransfer) -> Value {
+        let chain = transfer.chain;
+        let messages = i18n::messages();
+
+        json!({
+            "embeds": [{
+                "title": format!("🐋 {}", fill(messages.whale_transfer_on, &[&chain.name()])),
+                "url": chain.explorer_tx_url(&transfer.tx_hash),
+                "color": embed_color(chain),
+                "fields": [
+                    { "name": messages.amount, "value": transfer.amount_text(), "inline": true },
+                    { "name": messages.chain, "value": chain.name(), "inline": true },
+                    { "name": messages.block, "value": transfer.block_number.to_string(), "inline": true },
+                    { "name": messages.from, "value": address_link(chain, &transfer.from, &transfer.sender_name()) },
+                    { "name": messages.to, "value": address_link(chain, &transfer.to, &transfer.recipient_name()) }
+                ],
+                "timestamp": Utc::now().to_rfc3339()
+            }]
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
+    fn filter(&self) -> &TransferFilter {
+        &self.config.filter
+    }
+
+    async fn notify(&self, transfer: &WhaleTransfer) -> Result<()> {
+        let message = match &self.template {
+            Some(template) => json!({ "content": template.render(transfer)? }),
+            None => Self::render(transfer),
+        };
+        http::post_json(&self.client, &self.config.webhook_url, &message).await
+    }
+}
+
+/// Embed side bar color per chain
+fn embed_color(chain: Chain) -> u32 {
+    match chain {
+        Chain::Ethereum => 0x627eea,
+        Chain::Arbitrum => 0x28a0f0,
+        Chain::Base => 0x0052ff,
+    }
+}
+
+/// Render an address as a Markdown link with its label
+fn address_link(chain: Chain, address: &Address, label: &str) -> String {
+    format!(
+        "[{}]({}) ({})",
+        short_address(address),
+        chain.explorer_address_url(address),
+        escape(label)
+    )
+}
+
+/// Escape Discord Markdown control characters
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|' | '[' | ']' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{B256, U256};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_posts_embed() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let notifier = DiscordNotifier::new(
+            http::client().unwrap(),
+            WebhookChannelConfig {
+                webhook_url: format!("{}/hook", server.uri()),
+                template: None,
+                filter: TransferFilter::default(),
+            },
+        );
+        let transfer = WhaleTransfer::new(
+            Chain::Base,
+            B256::repeat_byte(1),
+            7,
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            U256::from(5_000_000_000_000u64),
+        )
+        .with_from_label(Some("*hot_wallet* [1]".to_string()));
+
+        notifier.notify(&transfer).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        let embed = &body["embeds"][0];
+        assert_eq!(embed["url"], Chain::Base.explorer_tx_url(&transfer.tx_hash));
+        assert_eq!(embed["color"], 0x0052ff);
+        assert_eq!(embed["fields"][2]["value"], "7");
+
+        let from = embed["fields"][3]["value"].as_str().unwrap();
+        assert!(from.starts_with("[0x0202"));
+        assert!(from.contains(&Chain::Base.explorer_address_url(&transfer.from)));
+        assert!(from.ends_with(r"(\*hot\_wallet\* \[1\])"));
+    }
+}
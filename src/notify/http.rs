@@ -0,0 +1,117 @@
+use eyre::{bail, Result};
+use reqwest::{Client, StatusCode};
+use serde::Serialize;
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// Timeout for a single webhook request
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Maximum delivery attempts per message
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry, doubled after each attempt
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Create the HTTP client shared by webhook notifiers
+pub fn client() -> Result<Client> {
+    Ok(Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()?)
+}
+
+/// POST a JSON body, retrying on connection errors, 429 and 5xx responses
+///
+/// On 429 the `Retry-After` header is honored when present.
+pub async fn post_json<T: Serialize + ?Sized>(client: &Client, url: &str, body: &T) -> Result<()> {
+    post_json_with_delay(client, url, body, BASE_RETRY_DELAY).await
+}
+
+async fn post_json_with_delay<T: Serialize + ?Sized>(
+    client: &Client,
+    url: &str,
+    body: &T,
+    base_delay: Duration,
+) -> Result<()> {
+    let mut delay = base_delay;
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let wait = match client.post(url).json(body).send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                let retry_after = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .map(Duration::from_secs_f64);
+                tracing::warn!(attempt, ?retry_after, "Webhook rate limited");
+                retry_after.unwrap_or(delay)
+            }
+            Ok(response) if response.status().is_server_error() => {
+                tracing::warn!(attempt, status = %response.status(), "Webhook server error");
+                delay
+            }
+            Ok(response) => {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                bail!("webhook rejected message: {} {}", status, text);
+            }
+            Err(e) => {
+                // Slack and Discord webhook URLs are credentials
+                tracing::warn!(attempt, error = %e.without_url(), "Webhook request failed");
+                delay
+            }
+        };
+
+        if attempt < MAX_ATTEMPTS {
+            sleep(wait).await;
+            delay *= 2;
+        }
+    }
+
+    bail!("webhook delivery failed after {} attempts", MAX_ATTEMPTS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_retries_rate_limit_then_succeeds() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client().unwrap();
+        post_json_with_delay(&client, &server.uri(), &serde_json::json!({}), Duration::ZERO)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_client_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client().unwrap();
+        let result =
+            post_json_with_delay(&client, &server.uri(), &serde_json::json!({}), Duration::ZERO)
+                .await;
+        assert!(result.is_err());
+    }
+}
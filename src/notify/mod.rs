@@ -0,0 +1,267 @@
+mod discord;
+mod http;
+mod queue;
+mod slack;
+mod telegram;
+mod webhook;
+
+pub use discord::DiscordNotifier;
+#[cfg(any(feature = "nats", feature = "kafka", feature = "redis", feature = "postgres"))]
+pub use queue::DiskQueue;
+pub use slack::SlackNotifier;
+pub use telegram::TelegramNotifier;
+pub use webhook::WebhookNotifier;
+
+use crate::config::NotifyConfig;
+use crate::filter::TransferFilter;
+use crate::template::Templates;
+use crate::types::WhaleTransfer;
+
+use async_trait::async_trait;
+use eyre::Result;
+use std::sync::{Arc, RwLock};
+use tokio::sync::mpsc;
+
+/// Queue size per notifier before transfers are dropped, or before
+/// dispatching waits for durable ones
+const NOTIFIER_QUEUE_SIZE: usize = 100;
+
+/// A destination that whale transfers are pushed to
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Human-readable name used in logs
+    fn name(&self) -> &str;
+
+    /// Filter deciding which transfers this notifier receives
+    fn filter(&self) -> &TransferFilter;
+
+    /// Deliver a single transfer
+    async fn notify(&self, transfer: &WhaleTransfer) -> Result<()>;
+
+    /// Whether transfers must never be dropped on a full queue
+    ///
+    /// Storage sinks return `true`, so a burst or a slow disk makes
+    /// [`Dispatcher::dispatch`] wait instead of losing transfers.
+    fn durable(&self) -> bool {
+        false
+    }
+}
+
+/// Build the chat notifiers enabled in the config
+///
+/// These hold no state beyond their HTTP client, so they can be rebuilt
+/// on reload. Webhooks own a disk queue and come from [`build_webhooks`].
+pub fn build_notifiers(
+    config: &NotifyConfig,
+    templates: &Arc<Templates>,
+) -> Result<Vec<Arc<dyn Notifier>>> {
+    let client = http::client()?;
+    let mut notifiers: Vec<Arc<dyn Notifier>> = Vec::new();
+    let template = |name: &Option<String>| name.as_deref().map(|n| templates.get(n)).transpose();
+
+    for channel in &config.slack {
+        notifiers.push(Arc::new(
+            SlackNotifier::new(client.clone(), channel.clone())
+                .with_template(template(&channel.template)?),
+        ));
+    }
+    for channel in &config.discord {
+        notifiers.push(Arc::new(
+            DiscordNotifier::new(client.clone(), channel.clone())
+                .with_template(template(&channel.template)?),
+        ));
+    }
+    if let Some(telegram) = &config.telegram {
+        for chat in &telegram.chats {
+            notifiers.push(Arc::new(
+                TelegramNotifier::new(client.clone(), telegram, chat.clone())
+                    .with_template(template(&chat.template)?),
+            ));
+        }
+    }
+
+    Ok(notifiers)
+}
+
+/// Build the webhook notifiers, each with its own delivery worker
+pub fn build_webhooks(
+    config: &NotifyConfig,
+    templates: &Arc<Templates>,
+) -> Result<Vec<Arc<dyn Notifier>>> {
+    let mut webhooks: Vec<Arc<dyn Notifier>> = Vec::new();
+    for (index, webhook) in config.webhook.iter().enumerate() {
+        let template = webhook.template.as_deref().map(|n| templates.get(n)).transpose()?;
+        webhooks.push(Arc::new(WebhookNotifier::spawn(webhook, index, template)?));
+    }
+    Ok(webhooks)
+}
+
+/// Queue feeding one notifier's delivery task
+struct Queue {
+    name: String,
+    durable: bool,
+    tx: mpsc::Sender<WhaleTransfer>,
+}
+
+/// Fans transfers out to notifiers, each running on its own task
+///
+/// A slow or failing notifier only fills its own queue and then drops
+/// transfers; it never blocks the monitors or the other notifiers. Durable
+/// sinks are the exception: a full queue makes dispatching wait, so storage
+/// is never skipped because it fell behind. What a sink fails to write is
+/// up to the sink; errors returned from [`Notifier::notify`] are only
+/// logged. Sinks are fixed for the lifetime of the process, while
+/// notifiers can be replaced on reload.
+pub struct Dispatcher {
+    sinks: Vec<Queue>,
+    notifiers: RwLock<Vec<Queue>>,
+}
+
+impl Dispatcher {
+    /// Spawn a delivery task for every sink and notifier
+    pub fn spawn(sinks: Vec<Arc<dyn Notifier>>, notifiers: Vec<Arc<dyn Notifier>>) -> Self {
+        Self {
+            sinks: sinks.into_iter().map(spawn_queue).collect(),
+            notifiers: RwLock::new(notifiers.into_iter().map(spawn_queue).collect()),
+        }
+    }
+
+    /// Swap the replaceable notifiers
+    ///
+    /// The old delivery tasks finish their queued transfers and exit.
+    pub fn replace_notifiers(&self, notifiers: Vec<Arc<dyn Notifier>>) {
+        let queues = notifiers.into_iter().map(spawn_queue).collect();
+        *self.notifiers.write().expect("notifiers lock poisoned") = queues;
+    }
+
+    /// Queue a transfer for every sink and notifier
+    ///
+    /// Waits for room in the queues of durable sinks; other queues drop the
+    /// transfer when full.
+    pub async fn dispatch(&self, transfer: &WhaleTransfer) {
+        for queue in &self.sinks {
+            if queue.durable {
+                if let Err(e) = queue.tx.send(transfer.clone()).await {
+                    tracing::error!(notifier = %queue.name, error = %e, "Sink stopped, dropping transfer");
+                }
+            } else {
+                queue.try_send(transfer);
+            }
+        }
+        let notifiers = self.notifiers.read().expect("notifiers lock poisoned");
+        for queue in notifiers.iter() {
+            queue.try_send(transfer);
+        }
+    }
+}
+
+impl Queue {
+    fn try_send(&self, transfer: &WhaleTransfer) {
+        if let Err(e) = self.tx.try_send(transfer.clone()) {
+            tracing::warn!(notifier = %self.name, error = %e, "Dropping notification");
+        }
+    }
+}
+
+/// Spawn the delivery task for one notifier
+fn spawn_queue(notifier: Arc<dyn Notifier>) -> Queue {
+    let (tx, mut rx) = mpsc::channel::<WhaleTransfer>(NOTIFIER_QUEUE_SIZE);
+    let name = notifier.name().to_string();
+    let durable = notifier.durable();
+
+    tokio::spawn(async move {
+        while let Some(transfer) = rx.recv().await {
+            if !notifier.filter().matches(&transfer) {
+                continue;
+            }
+            if let Err(e) = notifier.notify(&transfer).await {
+                tracing::error!(
+                    notifier = notifier.name(),
+                    error = %e,
+                    "Failed to deliver notification"
+                );
+            }
+        }
+    });
+
+    Queue { name, durable, tx }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Chain;
+    use alloy::primitives::{Address, B256, U256};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Slow sink counting what it received
+    #[derive(Default)]
+    struct Counter {
+        durable: bool,
+        delay_ms: u64,
+        received: AtomicUsize,
+        filter: TransferFilter,
+    }
+
+    #[async_trait]
+    impl Notifier for Counter {
+        fn name(&self) -> &str {
+            "counter"
+        }
+
+        fn filter(&self) -> &TransferFilter {
+            &self.filter
+        }
+
+        async fn notify(&self, _transfer: &WhaleTransfer) -> Result<()> {
+            tokio::time::sleep(Duration::from_millis(self.delay_ms)).await;
+            self.received.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        fn durable(&self) -> bool {
+            self.durable
+        }
+    }
+
+    #[tokio::test]
+    async fn test_durable_sinks_apply_backpressure() {
+        let durable = Arc::new(Counter {
+            durable: true,
+            delay_ms: 1,
+            ..Counter::default()
+        });
+        // Slower than the durable sink pacing the dispatcher, so its queue overflows
+        let lossy = Arc::new(Counter {
+            delay_ms: 10,
+            ..Counter::default()
+        });
+        let dispatcher = Dispatcher::spawn(
+            vec![Arc::clone(&durable) as Arc<dyn Notifier>, Arc::clone(&lossy) as Arc<dyn Notifier>],
+            Vec::new(),
+        );
+        let transfer = WhaleTransfer::new(
+            Chain::Base,
+            B256::repeat_byte(1),
+            1,
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            U256::from(1u64),
+        );
+
+        let count = NOTIFIER_QUEUE_SIZE * 2;
+        for _ in 0..count {
+            dispatcher.dispatch(&transfer).await;
+        }
+        drop(dispatcher);
+        for _ in 0..500 {
+            if durable.received.load(Ordering::Relaxed) == count {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(durable.received.load(Ordering::Relaxed), count);
+        assert!(lossy.received.load(Ordering::Relaxed) < count);
+    }
+}
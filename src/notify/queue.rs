@@ -0,0 +1,92 @@
+use eyre::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Directory-backed FIFO of pending deliveries
+///
+/// Each entry is one file named so that lexical order matches insertion
+/// order. Entries are written to a temporary file first and renamed, so a
+/// crash never leaves a half-written entry behind.
+pub struct DiskQueue {
+    dir: PathBuf,
+    failed_dir: PathBuf,
+    seq: AtomicU64,
+}
+
+impl DiskQueue {
+    /// Open (or create) a queue in the given directory
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        let failed_dir = dir.join("failed");
+        fs::create_dir_all(&failed_dir)?;
+
+        Ok(Self {
+            dir,
+            failed_dir,
+            seq: AtomicU64::new(0),
+        })
+    }
+
+    /// Append an entry
+    pub fn push(&self, body: &[u8]) -> Result<PathBuf> {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+        let seq = self.seq.fetch_add(1, Ordering::Relaxed);
+        let name = format!("{:024}-{:08}", nanos, seq);
+
+        let tmp = self.dir.join(format!("{}.tmp", name));
+        let path = self.dir.join(format!("{}.json", name));
+        fs::write(&tmp, body)?;
+        fs::rename(&tmp, &path)?;
+        Ok(path)
+    }
+
+    /// List pending entries, oldest first
+    pub fn pending(&self) -> Result<Vec<PathBuf>> {
+        let mut entries: Vec<PathBuf> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        entries.sort();
+        Ok(entries)
+    }
+
+    /// Remove a delivered entry
+    pub fn remove(&self, path: &Path) -> Result<()> {
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    /// Move an undeliverable entry to the `failed/` subdirectory
+    pub fn fail(&self, path: &Path) -> Result<()> {
+        if let Some(name) = path.file_name() {
+            fs::rename(path, self.failed_dir.join(name))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fifo_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let queue = DiskQueue::open(dir.path()).unwrap();
+            queue.push(b"first").unwrap();
+            queue.push(b"second").unwrap();
+        }
+
+        let queue = DiskQueue::open(dir.path()).unwrap();
+        let pending = queue.pending().unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(fs::read(&pending[0]).unwrap(), b"first");
+
+        queue.fail(&pending[0]).unwrap();
+        queue.remove(&pending[1]).unwrap();
+        assert!(queue.pending().unwrap().is_empty());
+    }
+}
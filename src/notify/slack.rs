@@ -0,0 +1,151 @@
+use super::{http, Notifier};
+use crate::config::WebhookChannelConfig;
+use crate::filter::TransferFilter;
+use crate::i18n::{self, fill};
+use crate::template::Template;
+use crate::types::{short_address, WhaleTransfer};
+
+use alloy::primitives::Address;
+use async_trait::async_trait;
+use eyre::Result;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+/// Posts whale alerts to a Slack incoming webhook using Block Kit
+pub struct SlackNotifier {
+    client: Client,
+    config: WebhookChannelConfig,
+    template: Option<Template>,
+}
+
+impl SlackNotifier {
+    /// Create a new Slack notifier
+    pub fn new(client: Client, config: WebhookChannelConfig) -> Self {
+        Self {
+            client,
+            config,
+            template: None,
+        }
+    }
+
+    /// Send a user-defined template as the message text instead of Block Kit
+    pub fn with_template(mut self, template: Option<Template>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Build the Block Kit message for a transfer
+    pub fn render(transfer: &WhaleTransfer) -> Value {
+        let chain = transfer.chain;
+        let messages = i18n::messages();
+        let tx_url = chain.explorer_tx_url(&transfer.tx_hash);
+        let summary = format!(
+            "🐋 {}",
+            fill(messages.whale_summary, &[&transfer.formatted_amount(), &chain.name()])
+        );
+
+        json!({
+            "text": summary,
+            "blocks": [
+                {
+                    "type": "header",
+                    "text": { "type": "plain_text", "text": format!("🐋 {}", fill(messages.whale_transfer_on, &[&chain.name()])) }
+                },
+                {
+                    "type": "section",
+                    "fields": [
+                        { "type": "mrkdwn", "text": format!("*{}*\n{}", messages.amount, transfer.amount_text()) },
+                        { "type": "mrkdwn", "text": format!("*{}*\n{}", messages.chain, chain.name()) },
+                        { "type": "mrkdwn", "text": format!("*{}*\n{}", messages.from, address_link(transfer, &transfer.from, &transfer.sender_name())) },
+                        { "type": "mrkdwn", "text": format!("*{}*\n{}", messages.to, address_link(transfer, &transfer.to, &transfer.recipient_name())) }
+                    ]
+                },
+                {
+                    "type": "context",
+                    "elements": [
+                        { "type": "mrkdwn", "text": format!("{} {} · <{}|{}>", messages.block, transfer.block_number, tx_url, transfer.short_tx_hash()) }
+                    ]
+                }
+            ]
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn name(&self) -> &str {
+        "slack"
+    }
+
+    fn filter(&self) -> &TransferFilter {
+        &self.config.filter
+    }
+
+    async fn notify(&self, transfer: &WhaleTransfer) -> Result<()> {
+        let message = match &self.template {
+            Some(template) => json!({ "text": template.render(transfer)? }),
+            None => Self::render(transfer),
+        };
+        http::post_json(&self.client, &self.config.webhook_url, &message).await
+    }
+}
+
+/// Render an address as a Slack link with its label
+fn address_link(transfer: &WhaleTransfer, address: &Address, label: &str) -> String {
+    let url = transfer.chain.explorer_address_url(address);
+    format!("<{}|{}> ({})", url, short_address(address), escape(label))
+}
+
+/// Escape the characters Slack treats as control sequences
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Chain;
+    use alloy::primitives::{B256, U256};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_posts_block_kit_message() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let notifier = SlackNotifier::new(
+            http::client().unwrap(),
+            WebhookChannelConfig {
+                webhook_url: format!("{}/hook", server.uri()),
+                template: None,
+                filter: TransferFilter::default(),
+            },
+        );
+        let transfer = WhaleTransfer::new(
+            Chain::Ethereum,
+            B256::repeat_byte(1),
+            1,
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            U256::from(5_000_000_000_000u64),
+        )
+        .with_from_label(Some("A <b> & C".to_string()));
+
+        notifier.notify(&transfer).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["text"], "🐋 $5,000,000.00 USDC on ETHEREUM");
+        let from = body["blocks"][1]["fields"][2]["text"].as_str().unwrap();
+        assert!(from.contains("https://etherscan.io/address/0x0202"));
+        assert!(from.contains("A &lt;b&gt; &amp; C"));
+    }
+}
@@ -0,0 +1,308 @@
+use super::Notifier;
+use crate::config::{TelegramChatConfig, TelegramConfig};
+use crate::filter::TransferFilter;
+use crate::i18n::{self, fill};
+use crate::template::Template;
+use crate::types::{short_address, WhaleTransfer};
+
+use alloy::primitives::Address;
+use async_trait::async_trait;
+use eyre::{bail, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+
+/// Maximum delivery attempts per message
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Telegram allows roughly one message per second to the same chat
+const MIN_CHAT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Posts whale alerts to a single Telegram chat through the Bot API
+pub struct TelegramNotifier {
+    client: Client,
+    endpoint: String,
+    chat: TelegramChatConfig,
+    template: Option<Template>,
+    last_sent: Mutex<Option<Instant>>,
+}
+
+/// Relevant part of a Bot API response
+#[derive(Debug, Deserialize)]
+struct ApiResponse {
+    ok: bool,
+    error_code: Option<u16>,
+    description: Option<String>,
+    parameters: Option<ResponseParameters>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseParameters {
+    retry_after: Option<u64>,
+}
+
+impl TelegramNotifier {
+    /// Create a notifier for one chat of the configured bot
+    pub fn new(client: Client, config: &TelegramConfig, chat: TelegramChatConfig) -> Self {
+        let endpoint = format!(
+            "{}/bot{}/sendMessage",
+            config.api_url.trim_end_matches('/'),
+            config.bot_token
+        );
+
+        Self {
+            client,
+            endpoint,
+            chat,
+            template: None,
+            last_sent: Mutex::new(None),
+        }
+    }
+
+    /// Send a user-defined template as plain text instead of the MarkdownV2 message
+    pub fn with_template(mut self, template: Option<Template>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Build the MarkdownV2 message for a transfer
+    pub fn render(transfer: &WhaleTransfer) -> String {
+        let chain = transfer.chain;
+        let messages = i18n::messages();
+        format!(
+            "🐋 *{}*\n\
+             *{}:* {}\n\
+             *{}:* {}\n\
+             *{}:* {}\n\
+             *{}:* {}\n\
+             [{}]({})",
+            escape(&fill(messages.whale_transfer_on, &[&chain.name()])),
+            escape(messages.amount),
+            escape(&transfer.amount_text()),
+            escape(messages.from),
+            address_link(transfer, &transfer.from, &transfer.sender_name()),
+            escape(messages.to),
+            address_link(transfer, &transfer.to, &transfer.recipient_name()),
+            escape(messages.block),
+            transfer.block_number,
+            escape(messages.view_transaction),
+            escape_url(&chain.explorer_tx_url(&transfer.tx_hash)),
+        )
+    }
+
+    /// Wait until the per-chat rate limit allows another message
+    async fn throttle(&self) {
+        let mut last_sent = self.last_sent.lock().await;
+        if let Some(at) = *last_sent {
+            let elapsed = at.elapsed();
+            if elapsed < MIN_CHAT_INTERVAL {
+                sleep(MIN_CHAT_INTERVAL - elapsed).await;
+            }
+        }
+        *last_sent = Some(Instant::now());
+    }
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    fn name(&self) -> &str {
+        "telegram"
+    }
+
+    fn filter(&self) -> &TransferFilter {
+        &self.chat.filter
+    }
+
+    async fn notify(&self, transfer: &WhaleTransfer) -> Result<()> {
+        let body = match &self.template {
+            Some(template) => json!({
+                "chat_id": self.chat.chat_id,
+                "text": template.render(transfer)?,
+                "disable_web_page_preview": true,
+            }),
+            None => json!({
+                "chat_id": self.chat.chat_id,
+                "text": Self::render(transfer),
+                "parse_mode": "MarkdownV2",
+                "disable_web_page_preview": true,
+            }),
+        };
+        let mut delay = Duration::from_secs(1);
+        let mut last_error = String::new();
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            self.throttle().await;
+
+            let wait = match self.client.post(&self.endpoint).json(&body).send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    let api: Option<ApiResponse> = response.json().await.ok();
+
+                    match api {
+                        Some(api) if api.ok => return Ok(()),
+                        Some(api) if api.error_code == Some(429) => {
+                            let retry_after = api
+                                .parameters
+                                .and_then(|p| p.retry_after)
+                                .map(Duration::from_secs)
+                                .unwrap_or(delay);
+                            tracing::warn!(attempt, ?retry_after, "Telegram rate limited");
+                            retry_after
+                        }
+                        _ if status.is_server_error() => {
+                            tracing::warn!(attempt, %status, "Telegram server error");
+                            delay
+                        }
+                        api => {
+                            let description = api.and_then(|a| a.description).unwrap_or_default();
+                            bail!("telegram rejected message: {} {}", status, description);
+                        }
+                    }
+                }
+                Err(e) => {
+                    // The URL holds the bot token
+                    let e = e.without_url();
+                    tracing::warn!(attempt, error = %e, "Telegram request failed");
+                    last_error = e.to_string();
+                    delay
+                }
+            };
+
+            if attempt < MAX_ATTEMPTS {
+                sleep(wait).await;
+                delay *= 2;
+            }
+        }
+
+        bail!("telegram delivery failed after {} attempts: {}", MAX_ATTEMPTS, last_error)
+    }
+}
+
+/// Render an address as a MarkdownV2 link with its label
+fn address_link(transfer: &WhaleTransfer, address: &Address, label: &str) -> String {
+    format!(
+        "[{}]({}) \\({}\\)",
+        escape(&short_address(address)),
+        escape_url(&transfer.chain.explorer_address_url(address)),
+        escape(label)
+    )
+}
+
+/// Escape text for Telegram MarkdownV2
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "_*[]()~`>#+-=|{}.!\\".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escape the inside of a MarkdownV2 link target
+fn escape_url(url: &str) -> String {
+    url.replace('\\', "\\\\").replace(')', "\\)")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ChatId;
+    use crate::types::Chain;
+    use alloy::primitives::{B256, U256};
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn notifier(server: &MockServer) -> TelegramNotifier {
+        let config = TelegramConfig {
+            bot_token: "123:abc".to_string(),
+            api_url: server.uri(),
+            chats: Vec::new(),
+        };
+        let chat = TelegramChatConfig {
+            template: None,
+            chat_id: ChatId::Id(-100),
+            filter: TransferFilter::default(),
+        };
+        TelegramNotifier::new(Client::new(), &config, chat)
+    }
+
+    fn transfer() -> WhaleTransfer {
+        WhaleTransfer::new(
+            Chain::Arbitrum,
+            B256::repeat_byte(1),
+            42,
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            U256::from(1_234_567_890_000u64),
+        )
+        .with_to_label(Some("Wintermute (MM)".to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_sends_escaped_markdown() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/bot123:abc/sendMessage"))
+            .and(body_partial_json(json!({ "chat_id": -100, "parse_mode": "MarkdownV2" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        notifier(&server).notify(&transfer()).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        let text = body["text"].as_str().unwrap();
+        assert!(text.contains("$1,234,567\\.89 USDC"));
+        assert!(text.contains("\\(Wintermute \\(MM\\)\\)"));
+    }
+
+    #[tokio::test]
+    async fn test_honors_retry_after() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429).set_body_json(json!({
+                "ok": false,
+                "error_code": 429,
+                "description": "Too Many Requests: retry after 0",
+                "parameters": { "retry_after": 0 }
+            })))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        notifier(&server).notify(&transfer()).await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_request_errors_hide_the_token() {
+        // Nothing listens on a freshly released port
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let config = TelegramConfig {
+            bot_token: "123:secret".to_string(),
+            api_url: format!("http://127.0.0.1:{}", port),
+            chats: Vec::new(),
+        };
+        let chat = TelegramChatConfig {
+            template: None,
+            chat_id: ChatId::Id(-100),
+            filter: TransferFilter::default(),
+        };
+        let notifier = TelegramNotifier::new(Client::new(), &config, chat);
+
+        let error = notifier.notify(&transfer()).await.unwrap_err().to_string();
+        assert!(error.contains("after 5 attempts"), "{}", error);
+        assert!(!error.contains("secret"), "{}", error);
+    }
+}
@@ -0,0 +1,342 @@
+use super::queue::DiskQueue;
+use super::Notifier;
+use crate::config::{WebhookConfig, WebhookFormat};
+use crate::filter::TransferFilter;
+use crate::template::Template;
+use crate::types::WhaleTransfer;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use eyre::Result;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::sleep;
+
+/// Header carrying the HMAC signature
+pub const SIGNATURE_HEADER: &str = "X-Whale-Signature";
+
+/// Header carrying the unix timestamp included in the signature
+pub const TIMESTAMP_HEADER: &str = "X-Whale-Timestamp";
+
+/// CloudEvents `type` attribute for transfers
+const CLOUDEVENTS_TYPE: &str = "io.github.usdc_whale_detector.transfer";
+
+/// Upper bound for the retry backoff
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
+
+/// POSTs serialized transfers to an HTTP endpoint
+///
+/// Transfers are written to a [`DiskQueue`] first and delivered by a
+/// background task, so pending deliveries survive restarts and receiver
+/// outages. Non-retryable rejections (4xx other than 408/429) are moved to
+/// the queue's `failed/` directory.
+pub struct WebhookNotifier {
+    name: String,
+    format: WebhookFormat,
+    template: Option<Template>,
+    filter: TransferFilter,
+    queue: Arc<DiskQueue>,
+    wake: Arc<Notify>,
+}
+
+/// Outcome of a single delivery attempt
+enum Delivery {
+    Delivered,
+    Retry,
+    Rejected,
+}
+
+impl WebhookNotifier {
+    /// Create the notifier and start its delivery task
+    ///
+    /// With a template, bodies are rendered from it and sent with the
+    /// configured `content_type` instead of `format`.
+    pub fn spawn(config: &WebhookConfig, index: usize, template: Option<Template>) -> Result<Self> {
+        let name = config
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("webhook-{}", index));
+        let queue_dir = config
+            .queue_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("queue").join(&name));
+        let queue = Arc::new(DiskQueue::open(&queue_dir)?);
+        let wake = Arc::new(Notify::new());
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()?;
+        let worker = Worker {
+            name: name.clone(),
+            client,
+            url: config.url.clone(),
+            secret: config.secret.clone(),
+            content_type: match (&template, config.format) {
+                (Some(_), _) => config.content_type.clone(),
+                (None, WebhookFormat::Json) => "application/json".to_string(),
+                (None, WebhookFormat::CloudEvents) => "application/cloudevents+json".to_string(),
+            },
+            queue: Arc::clone(&queue),
+            wake: Arc::clone(&wake),
+            base_delay: Duration::from_secs(1),
+        };
+        tokio::spawn(worker.run());
+
+        Ok(Self {
+            name,
+            format: config.format,
+            template,
+            filter: config.filter.clone(),
+            queue,
+            wake,
+        })
+    }
+
+    /// Serialize a transfer in the configured format
+    pub fn render(&self, transfer: &WhaleTransfer) -> Result<Vec<u8>> {
+        if let Some(template) = &self.template {
+            return Ok(template.render(transfer)?.into_bytes());
+        }
+
+        let body = match self.format {
+            WebhookFormat::Json => serde_json::to_vec(transfer)?,
+            WebhookFormat::CloudEvents => serde_json::to_vec(&json!({
+                "specversion": "1.0",
+                "type": CLOUDEVENTS_TYPE,
+                "source": format!("/usdc_whale_detector/{}", transfer.chain.name().to_lowercase()),
+                "id": event_id(transfer),
+                "time": Utc::now().to_rfc3339(),
+                "datacontenttype": "application/json",
+                "data": transfer,
+            }))?,
+        };
+        Ok(body)
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn filter(&self) -> &TransferFilter {
+        &self.filter
+    }
+
+    async fn notify(&self, transfer: &WhaleTransfer) -> Result<()> {
+        self.queue.push(&self.render(transfer)?)?;
+        self.wake.notify_one();
+        Ok(())
+    }
+}
+
+/// Background task draining the disk queue
+struct Worker {
+    name: String,
+    client: Client,
+    url: String,
+    secret: Option<String>,
+    content_type: String,
+    queue: Arc<DiskQueue>,
+    wake: Arc<Notify>,
+    base_delay: Duration,
+}
+
+impl Worker {
+    async fn run(self) {
+        let mut delay = self.base_delay;
+
+        loop {
+            let pending = match self.queue.pending() {
+                Ok(pending) => pending,
+                Err(e) => {
+                    tracing::error!(webhook = %self.name, error = %e, "Failed to read queue");
+                    Vec::new()
+                }
+            };
+
+            if pending.is_empty() {
+                self.wake.notified().await;
+                continue;
+            }
+
+            for path in pending {
+                let body = match std::fs::read(&path) {
+                    Ok(body) => body,
+                    Err(e) => {
+                        tracing::error!(webhook = %self.name, error = %e, "Unreadable queued delivery");
+                        if let Err(e) = self.queue.fail(&path) {
+                            // Back off rather than re-listing the same entry in a hot loop
+                            tracing::error!(webhook = %self.name, error = %e, "Failed to update queue");
+                            sleep(delay).await;
+                            delay = (delay * 2).min(MAX_RETRY_DELAY);
+                            break;
+                        }
+                        continue;
+                    }
+                };
+
+                let result = match self.deliver(&body).await {
+                    Delivery::Delivered => self.queue.remove(&path),
+                    Delivery::Rejected => self.queue.fail(&path),
+                    Delivery::Retry => {
+                        sleep(delay).await;
+                        delay = (delay * 2).min(MAX_RETRY_DELAY);
+                        break;
+                    }
+                };
+                if let Err(e) = result {
+                    tracing::error!(webhook = %self.name, error = %e, "Failed to update queue");
+                }
+                delay = self.base_delay;
+            }
+        }
+    }
+
+    async fn deliver(&self, body: &[u8]) -> Delivery {
+        let timestamp = Utc::now().timestamp().to_string();
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, &self.content_type)
+            .header(TIMESTAMP_HEADER, &timestamp);
+        if let Some(secret) = &self.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, &timestamp, body));
+        }
+
+        match request.body(body.to_vec()).send().await {
+            Ok(response) if response.status().is_success() => Delivery::Delivered,
+            Ok(response)
+                if response.status().is_server_error()
+                    || response.status() == StatusCode::TOO_MANY_REQUESTS
+                    || response.status() == StatusCode::REQUEST_TIMEOUT =>
+            {
+                tracing::warn!(webhook = %self.name, status = %response.status(), "Webhook delivery will be retried");
+                Delivery::Retry
+            }
+            Ok(response) => {
+                tracing::error!(webhook = %self.name, status = %response.status(), "Webhook rejected delivery");
+                Delivery::Rejected
+            }
+            Err(e) => {
+                tracing::warn!(webhook = %self.name, error = %e.without_url(), "Webhook delivery will be retried");
+                Delivery::Retry
+            }
+        }
+    }
+}
+
+/// Compute the signature header value: `sha256=<hex hmac of "{timestamp}.{body}">`
+pub fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Stable event id derived from the transfer contents
+fn event_id(transfer: &WhaleTransfer) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(transfer.chain.chain_id().to_be_bytes());
+    hasher.update(transfer.tx_hash);
+    hasher.update(transfer.log_index.to_be_bytes());
+    hasher.update(transfer.from);
+    hasher.update(transfer.to);
+    hasher.update(transfer.amount_raw.to_be_bytes::<32>());
+    hex::encode(&hasher.finalize()[..16])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Chain;
+    use alloy::primitives::{Address, B256, U256};
+    use wiremock::matchers::{header, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_event_id_is_unique_per_log() {
+        let transfer = WhaleTransfer::new(
+            Chain::Base,
+            B256::repeat_byte(1),
+            7,
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            U256::from(1_000_000u64),
+        );
+        let same_tx = transfer.clone().with_log_index(1);
+
+        assert_eq!(event_id(&transfer), event_id(&transfer.clone()));
+        assert_ne!(event_id(&transfer), event_id(&same_tx));
+    }
+
+    #[tokio::test]
+    async fn test_delivers_queued_events_after_restart() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("content-type", "application/cloudevents+json"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = WebhookConfig {
+            name: None,
+            url: server.uri(),
+            secret: Some("s3cret".to_string()),
+            format: WebhookFormat::CloudEvents,
+            template: None,
+            content_type: String::new(),
+            timeout_secs: 5,
+            queue_dir: Some(dir.path().to_path_buf()),
+            filter: TransferFilter::default(),
+        };
+
+        // Simulate a delivery left over from a previous run
+        DiskQueue::open(dir.path()).unwrap().push(b"{\"left\":\"over\"}").unwrap();
+        // and one that can't be read, which must not stall the queue
+        std::fs::create_dir(dir.path().join("0-unreadable.json")).unwrap();
+
+        let notifier = WebhookNotifier::spawn(&config, 0, None).unwrap();
+        let transfer = WhaleTransfer::new(
+            Chain::Ethereum,
+            B256::repeat_byte(1),
+            1,
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            U256::from(2_000_000_000_000u64),
+        );
+        notifier.notify(&transfer).await.unwrap();
+
+        for _ in 0..50 {
+            if notifier.queue.pending().unwrap().is_empty() {
+                break;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+        assert!(notifier.queue.pending().unwrap().is_empty());
+        assert!(dir.path().join("failed").join("0-unreadable.json").exists());
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].body, b"{\"left\":\"over\"}");
+
+        let event = &requests[1];
+        let body: serde_json::Value = serde_json::from_slice(&event.body).unwrap();
+        assert_eq!(body["specversion"], "1.0");
+        assert_eq!(body["data"]["amount"], "2000000.000000");
+
+        let timestamp = event.headers.get(TIMESTAMP_HEADER).unwrap().to_str().unwrap();
+        let signature = event.headers.get(SIGNATURE_HEADER).unwrap().to_str().unwrap();
+        assert_eq!(signature, sign("s3cret", timestamp, &event.body));
+    }
+}
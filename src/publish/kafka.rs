@@ -0,0 +1,106 @@
+use super::{Message, Publisher};
+use crate::config::KafkaConfig;
+
+use async_trait::async_trait;
+use eyre::Result;
+use rdkafka::config::ClientConfig;
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::util::Timeout;
+use std::time::Duration;
+
+/// Header carrying the message id
+const MSG_ID_HEADER: &str = "message-id";
+
+/// How long librdkafka may keep retrying one message before reporting failure
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Publishes to Kafka topics, keyed by chain so each chain stays ordered within a partition
+///
+/// The producer waits for all in-sync replicas (`acks=all`) and is idempotent,
+/// so librdkafka's internal retries don't create duplicates. Settings under
+/// `properties` override these defaults.
+pub struct KafkaPublisher {
+    producer: FutureProducer,
+}
+
+impl KafkaPublisher {
+    pub fn new(config: &KafkaConfig) -> Result<Self> {
+        let mut client = ClientConfig::new();
+        client
+            .set("bootstrap.servers", &config.brokers)
+            .set("acks", "all")
+            .set("enable.idempotence", "true")
+            .set("message.timeout.ms", DELIVERY_TIMEOUT.as_millis().to_string());
+        for (key, value) in &config.properties {
+            client.set(key, value);
+        }
+
+        Ok(Self {
+            producer: client.create()?,
+        })
+    }
+}
+
+#[async_trait]
+impl Publisher for KafkaPublisher {
+    async fn publish(&mut self, message: &Message) -> Result<()> {
+        let headers = OwnedHeaders::new().insert(Header {
+            key: MSG_ID_HEADER,
+            value: Some(message.id.as_str()),
+        });
+        let record = FutureRecord::to(&message.destination)
+            .key(&message.key)
+            .payload(&message.payload)
+            .headers(headers);
+
+        self.producer
+            .send(record, Timeout::After(DELIVERY_TIMEOUT))
+            .await
+            .map_err(|(e, _)| e)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdkafka::consumer::{Consumer, StreamConsumer};
+    use rdkafka::Message as _;
+
+    /// Needs a Kafka broker: `TEST_KAFKA_BROKERS=127.0.0.1:9092 cargo test --features kafka -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_publish_to_kafka() {
+        let brokers = std::env::var("TEST_KAFKA_BROKERS").expect("TEST_KAFKA_BROKERS not set");
+        let topic = format!("whales-test-{}", std::process::id());
+
+        let mut publisher = KafkaPublisher::new(&KafkaConfig {
+            name: None,
+            brokers: brokers.clone(),
+            topic: topic.clone(),
+            properties: Default::default(),
+            queue_dir: None,
+            filter: Default::default(),
+        })
+        .unwrap();
+        let message = Message {
+            destination: topic.clone(),
+            key: "base".to_string(),
+            id: "base:0x01:0".to_string(),
+            payload: b"{}".to_vec(),
+        };
+        publisher.publish(&message).await.unwrap();
+
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &brokers)
+            .set("group.id", &topic)
+            .set("auto.offset.reset", "earliest")
+            .create()
+            .unwrap();
+        consumer.subscribe(&[&topic]).unwrap();
+        let received = consumer.recv().await.unwrap();
+        assert_eq!(received.key(), Some(&b"base"[..]));
+        assert_eq!(received.payload(), Some(&b"{}"[..]));
+    }
+}
@@ -0,0 +1,360 @@
+#[cfg(feature = "kafka")]
+mod kafka;
+#[cfg(feature = "nats")]
+mod nats;
+#[cfg(feature = "redis")]
+mod redis;
+
+use crate::config::PublishConfig;
+use crate::filter::TransferFilter;
+use crate::notify::{DiskQueue, Notifier};
+use crate::types::WhaleTransfer;
+
+use async_trait::async_trait;
+use eyre::Result;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::sleep;
+
+/// Token placeholder value; only USDC is monitored
+const TOKEN: &str = "usdc";
+
+/// Upper bound for the retry backoff
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
+
+/// A single message handed to a broker
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    /// Rendered subject, topic or stream key
+    pub destination: String,
+    /// Partition / routing key (the chain name)
+    pub key: String,
+    /// Stable id consumers can use to drop redelivered duplicates
+    pub id: String,
+    /// The transfer serialized with the JSON Lines schema
+    pub payload: Vec<u8>,
+}
+
+/// A broker client; connects lazily and returns only once the broker accepted the message
+#[async_trait]
+pub trait Publisher: Send + 'static {
+    async fn publish(&mut self, message: &Message) -> Result<()>;
+}
+
+/// Build all publishers enabled in the config
+pub fn build_publishers(config: &PublishConfig) -> Result<Vec<Arc<dyn Notifier>>> {
+    let mut publishers: Vec<Arc<dyn Notifier>> = Vec::new();
+
+    #[cfg(feature = "nats")]
+    for (index, nats) in config.nats.iter().enumerate() {
+        let name = nats.name.clone().unwrap_or_else(|| format!("nats-{}", index));
+        publishers.push(Arc::new(PublisherSink::spawn(
+            name,
+            &nats.subject,
+            nats.queue_dir.clone(),
+            nats.filter.clone(),
+            nats::NatsPublisher::new(nats),
+        )?));
+    }
+    #[cfg(not(feature = "nats"))]
+    if !config.nats.is_empty() {
+        eyre::bail!("publish.nats is configured but this build lacks the `nats` feature");
+    }
+
+    #[cfg(feature = "kafka")]
+    for (index, kafka) in config.kafka.iter().enumerate() {
+        let name = kafka.name.clone().unwrap_or_else(|| format!("kafka-{}", index));
+        publishers.push(Arc::new(PublisherSink::spawn(
+            name,
+            &kafka.topic,
+            kafka.queue_dir.clone(),
+            kafka.filter.clone(),
+            kafka::KafkaPublisher::new(kafka)?,
+        )?));
+    }
+    #[cfg(not(feature = "kafka"))]
+    if !config.kafka.is_empty() {
+        eyre::bail!("publish.kafka is configured but this build lacks the `kafka` feature");
+    }
+
+    #[cfg(feature = "redis")]
+    for (index, redis) in config.redis.iter().enumerate() {
+        let name = redis.name.clone().unwrap_or_else(|| format!("redis-{}", index));
+        publishers.push(Arc::new(PublisherSink::spawn(
+            name,
+            &redis.stream,
+            redis.queue_dir.clone(),
+            redis.filter.clone(),
+            redis::RedisPublisher::new(redis)?,
+        )?));
+    }
+    #[cfg(not(feature = "redis"))]
+    if !config.redis.is_empty() {
+        eyre::bail!("publish.redis is configured but this build lacks the `redis` feature");
+    }
+
+    Ok(publishers)
+}
+
+/// Expand `{chain}`, `{chain_id}`, `{token}` and `{status}` in a subject/topic/stream template
+pub fn render_template(template: &str, transfer: &WhaleTransfer) -> String {
+    template
+        .replace("{chain}", &transfer.chain.name().to_lowercase())
+        .replace("{chain_id}", &transfer.chain.chain_id().to_string())
+        .replace("{token}", TOKEN)
+        .replace("{status}", transfer.status.as_str())
+}
+
+/// Reject templates with unknown placeholders
+fn check_template(template: &str) -> Result<()> {
+    let stripped = ["{chain}", "{chain_id}", "{token}", "{status}"]
+        .iter()
+        .fold(template.to_string(), |t, placeholder| t.replace(placeholder, ""));
+    if stripped.contains(['{', '}']) {
+        eyre::bail!("unknown placeholder in template {:?}", template);
+    }
+    Ok(())
+}
+
+/// Message id, unique per Transfer log: `{chain}:{tx_hash}:{log_index}`
+pub fn message_id(transfer: &WhaleTransfer) -> String {
+    format!(
+        "{}:{:?}:{}",
+        transfer.chain.name().to_lowercase(),
+        transfer.tx_hash,
+        transfer.log_index
+    )
+}
+
+/// Sink publishing transfers to a broker with at-least-once delivery
+///
+/// The sink is durable, so the dispatcher waits for it rather than dropping
+/// transfers. Once in its [`DiskQueue`], a transfer is only removed after the
+/// broker acknowledged it, so it survives broker outages and restarts.
+/// Transfers still in memory on their way to the queue are lost if the
+/// process is killed. A message may be delivered twice if the process stops
+/// between the ack and the removal; consumers deduplicate on [`message_id`].
+pub struct PublisherSink {
+    name: String,
+    filter: TransferFilter,
+    queue: Arc<DiskQueue>,
+    wake: Arc<Notify>,
+}
+
+impl PublisherSink {
+    /// Create the sink and start its delivery task
+    pub fn spawn(
+        name: String,
+        template: &str,
+        queue_dir: Option<PathBuf>,
+        filter: TransferFilter,
+        publisher: impl Publisher,
+    ) -> Result<Self> {
+        check_template(template)?;
+        let queue_dir = queue_dir.unwrap_or_else(|| PathBuf::from("queue").join(&name));
+        let queue = Arc::new(DiskQueue::open(&queue_dir)?);
+        let wake = Arc::new(Notify::new());
+
+        let worker = Worker {
+            name: name.clone(),
+            template: template.to_string(),
+            publisher,
+            queue: Arc::clone(&queue),
+            wake: Arc::clone(&wake),
+            base_delay: Duration::from_secs(1),
+        };
+        tokio::spawn(worker.run());
+
+        Ok(Self {
+            name,
+            filter,
+            queue,
+            wake,
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for PublisherSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn filter(&self) -> &TransferFilter {
+        &self.filter
+    }
+
+    fn durable(&self) -> bool {
+        true
+    }
+
+    async fn notify(&self, transfer: &WhaleTransfer) -> Result<()> {
+        self.queue.push(&serde_json::to_vec(transfer)?)?;
+        self.wake.notify_one();
+        Ok(())
+    }
+}
+
+/// Background task draining the disk queue in order
+struct Worker<P> {
+    name: String,
+    template: String,
+    publisher: P,
+    queue: Arc<DiskQueue>,
+    wake: Arc<Notify>,
+    base_delay: Duration,
+}
+
+impl<P: Publisher> Worker<P> {
+    async fn run(mut self) {
+        let mut delay = self.base_delay;
+
+        loop {
+            let pending = match self.queue.pending() {
+                Ok(pending) => pending,
+                Err(e) => {
+                    tracing::error!(publisher = %self.name, error = %e, "Failed to read queue");
+                    Vec::new()
+                }
+            };
+
+            if pending.is_empty() {
+                self.wake.notified().await;
+                continue;
+            }
+
+            for path in pending {
+                let message = match std::fs::read(&path)
+                    .map_err(eyre::Report::from)
+                    .and_then(|payload| self.message(payload))
+                {
+                    Ok(message) => message,
+                    Err(e) => {
+                        tracing::error!(publisher = %self.name, error = %e, "Unreadable queued message");
+                        if let Err(e) = self.queue.fail(&path) {
+                            tracing::error!(publisher = %self.name, error = %e, "Failed to update queue");
+                        }
+                        continue;
+                    }
+                };
+
+                if let Err(e) = self.publisher.publish(&message).await {
+                    tracing::warn!(publisher = %self.name, error = %e, "Publish will be retried");
+                    sleep(delay).await;
+                    delay = (delay * 2).min(MAX_RETRY_DELAY);
+                    break;
+                }
+                if let Err(e) = self.queue.remove(&path) {
+                    tracing::error!(publisher = %self.name, error = %e, "Failed to update queue");
+                }
+                delay = self.base_delay;
+            }
+        }
+    }
+
+    fn message(&self, payload: Vec<u8>) -> Result<Message> {
+        let transfer: WhaleTransfer = serde_json::from_slice(&payload)?;
+        Ok(Message {
+            destination: render_template(&self.template, &transfer),
+            key: transfer.chain.name().to_lowercase(),
+            id: message_id(&transfer),
+            payload,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Chain, TransferStatus};
+    use alloy::primitives::{Address, B256, U256};
+    use std::sync::Mutex;
+
+    fn transfer(chain: Chain, log_index: u64) -> WhaleTransfer {
+        WhaleTransfer::new(
+            chain,
+            B256::repeat_byte(1),
+            100,
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            U256::from(2_000_000_000_000u64),
+        )
+        .with_log_index(log_index)
+    }
+
+    /// Broker stand-in that rejects the first `failures` publishes
+    struct FakeBroker {
+        failures: usize,
+        received: Arc<Mutex<Vec<Message>>>,
+    }
+
+    #[async_trait]
+    impl Publisher for FakeBroker {
+        async fn publish(&mut self, message: &Message) -> Result<()> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                eyre::bail!("broker unavailable");
+            }
+            self.received.lock().unwrap().push(message.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_templates() {
+        let t = transfer(Chain::Arbitrum, 7).with_status(TransferStatus::Removed);
+        assert_eq!(
+            render_template("whales.{token}.{chain}.{status}", &t),
+            "whales.usdc.arbitrum.removed"
+        );
+        assert_eq!(render_template("whales:{chain_id}", &t), "whales:42161");
+        assert!(check_template("whales.{chain}").is_ok());
+        assert!(check_template("whales.{network}").is_err());
+        assert!(message_id(&t).starts_with("arbitrum:0x0101"));
+        assert!(message_id(&t).ends_with(":7"));
+    }
+
+    #[tokio::test]
+    async fn test_retries_in_order_until_acked() {
+        let dir = tempfile::tempdir().unwrap();
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        // A message left over from a previous run is published first
+        DiskQueue::open(dir.path())
+            .unwrap()
+            .push(&serde_json::to_vec(&transfer(Chain::Ethereum, 1)).unwrap())
+            .unwrap();
+
+        let sink = PublisherSink::spawn(
+            "fake".to_string(),
+            "whales.{chain}",
+            Some(dir.path().to_path_buf()),
+            TransferFilter::default(),
+            FakeBroker {
+                failures: 2,
+                received: Arc::clone(&received),
+            },
+        )
+        .unwrap();
+        sink.notify(&transfer(Chain::Base, 2)).await.unwrap();
+
+        // Two failures back off for 1s + 2s
+        for _ in 0..100 {
+            if sink.queue.pending().unwrap().is_empty() {
+                break;
+            }
+            sleep(Duration::from_millis(50)).await;
+        }
+        assert!(sink.queue.pending().unwrap().is_empty());
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].destination, "whales.ethereum");
+        assert_eq!(received[1].destination, "whales.base");
+        assert_eq!(received[1].key, "base");
+        let payload: serde_json::Value = serde_json::from_slice(&received[1].payload).unwrap();
+        assert_eq!(payload["amount"], "2000000.000000");
+    }
+}
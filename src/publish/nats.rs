@@ -0,0 +1,95 @@
+use super::{Message, Publisher};
+use crate::config::NatsConfig;
+
+use async_nats::jetstream;
+use async_nats::HeaderMap;
+use async_trait::async_trait;
+use eyre::Result;
+
+/// Header JetStream uses to drop duplicate publishes
+const MSG_ID_HEADER: &str = "Nats-Msg-Id";
+
+/// Publishes to NATS subjects
+///
+/// With JetStream each publish waits for the stream's ack, and the message id
+/// lets the stream discard redeliveries within its duplicate window. Core NATS
+/// has no acks, so without JetStream delivery is only confirmed up to the
+/// server.
+pub struct NatsPublisher {
+    url: String,
+    jetstream: bool,
+    client: Option<async_nats::Client>,
+}
+
+impl NatsPublisher {
+    pub fn new(config: &NatsConfig) -> Self {
+        Self {
+            url: config.url.clone(),
+            jetstream: config.jetstream,
+            client: None,
+        }
+    }
+
+    async fn client(&mut self) -> Result<async_nats::Client> {
+        if let Some(client) = &self.client {
+            return Ok(client.clone());
+        }
+        let client = async_nats::connect(&self.url).await?;
+        self.client = Some(client.clone());
+        Ok(client)
+    }
+}
+
+#[async_trait]
+impl Publisher for NatsPublisher {
+    async fn publish(&mut self, message: &Message) -> Result<()> {
+        let client = self.client().await?;
+        let mut headers = HeaderMap::new();
+        headers.insert(MSG_ID_HEADER, message.id.as_str());
+        let subject = message.destination.clone();
+        let payload = message.payload.clone().into();
+
+        if self.jetstream {
+            jetstream::new(client)
+                .publish_with_headers(subject, headers, payload)
+                .await?
+                .await?;
+        } else {
+            client.publish_with_headers(subject, headers, payload).await?;
+            client.flush().await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    /// Needs a NATS server: `TEST_NATS_URL=nats://127.0.0.1:4222 cargo test --features nats -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_publish_to_nats() {
+        let url = std::env::var("TEST_NATS_URL").expect("TEST_NATS_URL not set");
+        let client = async_nats::connect(&url).await.unwrap();
+        let mut subscriber = client.subscribe("whales.test.>").await.unwrap();
+
+        let mut publisher = NatsPublisher {
+            url,
+            jetstream: false,
+            client: None,
+        };
+        let message = Message {
+            destination: "whales.test.base".to_string(),
+            key: "base".to_string(),
+            id: "base:0x01:0".to_string(),
+            payload: b"{}".to_vec(),
+        };
+        publisher.publish(&message).await.unwrap();
+
+        let received = subscriber.next().await.unwrap();
+        assert_eq!(received.subject.as_str(), "whales.test.base");
+        assert_eq!(received.headers.unwrap().get(MSG_ID_HEADER).unwrap().as_str(), "base:0x01:0");
+    }
+}
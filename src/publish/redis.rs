@@ -0,0 +1,98 @@
+use super::{Message, Publisher};
+use crate::config::RedisConfig;
+
+use async_trait::async_trait;
+use eyre::Result;
+use redis::aio::ConnectionManager;
+
+/// Appends to Redis Streams with `XADD`
+///
+/// Each entry has the fields `id` (for deduplication), `chain` and `data`
+/// (the transfer JSON).
+pub struct RedisPublisher {
+    client: redis::Client,
+    max_len: Option<usize>,
+    connection: Option<ConnectionManager>,
+}
+
+impl RedisPublisher {
+    pub fn new(config: &RedisConfig) -> Result<Self> {
+        Ok(Self {
+            client: redis::Client::open(config.url.as_str())?,
+            max_len: config.max_len,
+            connection: None,
+        })
+    }
+
+    async fn connection(&mut self) -> Result<ConnectionManager> {
+        if let Some(connection) = &self.connection {
+            return Ok(connection.clone());
+        }
+        let connection = self.client.get_connection_manager().await?;
+        self.connection = Some(connection.clone());
+        Ok(connection)
+    }
+}
+
+#[async_trait]
+impl Publisher for RedisPublisher {
+    async fn publish(&mut self, message: &Message) -> Result<()> {
+        let mut connection = self.connection().await?;
+
+        let mut command = redis::cmd("XADD");
+        command.arg(&message.destination);
+        if let Some(max_len) = self.max_len {
+            command.arg("MAXLEN").arg("~").arg(max_len);
+        }
+        command
+            .arg("*")
+            .arg("id")
+            .arg(&message.id)
+            .arg("chain")
+            .arg(&message.key)
+            .arg("data")
+            .arg(&message.payload);
+
+        let _: String = command.query_async(&mut connection).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::streams::StreamRangeReply;
+    use redis::AsyncCommands;
+
+    /// Needs a Redis server: `TEST_REDIS_URL=redis://127.0.0.1:6379 cargo test --features redis -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_publish_to_redis() {
+        let url = std::env::var("TEST_REDIS_URL").expect("TEST_REDIS_URL not set");
+        let stream = format!("whales-test:{}", std::process::id());
+
+        let mut publisher = RedisPublisher::new(&RedisConfig {
+            name: None,
+            url,
+            stream: stream.clone(),
+            max_len: Some(100),
+            queue_dir: None,
+            filter: Default::default(),
+        })
+        .unwrap();
+        let message = Message {
+            destination: stream.clone(),
+            key: "base".to_string(),
+            id: "base:0x01:0".to_string(),
+            payload: b"{}".to_vec(),
+        };
+        publisher.publish(&message).await.unwrap();
+
+        let mut connection = publisher.connection().await.unwrap();
+        let reply: StreamRangeReply = connection.xrange_all(&stream).await.unwrap();
+        let _: () = connection.del(&stream).await.unwrap();
+        assert_eq!(reply.ids.len(), 1);
+        let id: String = reply.ids[0].get("id").unwrap();
+        assert_eq!(id, "base:0x01:0");
+    }
+}
@@ -0,0 +1,160 @@
+use crate::cli::{QueryArgs, QueryFormat};
+use crate::config::AppConfig;
+use crate::store::{DailyTotal, SqliteStore, TransferQuery};
+use crate::types::{format_with_commas, WhaleTransfer};
+
+use eyre::{eyre, Result};
+use std::io::{self, Write};
+
+/// Run the `query` subcommand
+pub fn run(args: QueryArgs, config: &AppConfig) -> Result<()> {
+    let path = args
+        .db
+        .clone()
+        .or_else(|| config.history.sqlite_path.clone())
+        .ok_or_else(|| eyre!("no history database: pass --db or set history.sqlite_path"))?;
+    if !path.exists() {
+        return Err(eyre!("history database {} does not exist", path.display()));
+    }
+    let store = SqliteStore::open(&path)?;
+
+    let query = TransferQuery {
+        chains: args.chain,
+        address: args.address,
+        label: args.label,
+        min_amount_usd: args.min_amount,
+        max_amount_usd: args.max_amount,
+        since: args.since,
+        until: args.until,
+        sort: args.sort,
+        ascending: args.asc,
+        limit: Some(args.limit),
+        offset: 0,
+    };
+
+    let mut out = io::stdout().lock();
+    if args.daily {
+        let totals = store.daily_totals(&query)?;
+        match args.format {
+            QueryFormat::Table => print_totals_table(&mut out, &totals)?,
+            QueryFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(out);
+                for total in &totals {
+                    writer.serialize(total)?;
+                }
+                writer.flush()?;
+            }
+            QueryFormat::Json => {
+                serde_json::to_writer_pretty(&mut out, &totals)?;
+                writeln!(out)?;
+            }
+        }
+    } else {
+        let transfers = store.query(&query)?;
+        match args.format {
+            QueryFormat::Table => print_transfers_table(&mut out, &transfers)?,
+            QueryFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(out);
+                for transfer in &transfers {
+                    writer.serialize(csv_row(transfer))?;
+                }
+                writer.flush()?;
+            }
+            QueryFormat::Json => {
+                serde_json::to_writer_pretty(&mut out, &transfers)?;
+                writeln!(out)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Flat CSV row for a transfer
+#[derive(serde::Serialize)]
+struct CsvRow<'a> {
+    chain: String,
+    block_number: u64,
+    block_timestamp: String,
+    tx_hash: String,
+    log_index: u64,
+    from: String,
+    from_label: &'a str,
+    to: String,
+    to_label: &'a str,
+    amount_usd: f64,
+    status: &'static str,
+}
+
+fn csv_row(transfer: &WhaleTransfer) -> CsvRow<'_> {
+    CsvRow {
+        chain: transfer.chain.name().to_lowercase(),
+        block_number: transfer.block_number,
+        block_timestamp: transfer
+            .block_timestamp
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default(),
+        tx_hash: format!("{:?}", transfer.tx_hash),
+        log_index: transfer.log_index,
+        from: format!("{:?}", transfer.from),
+        from_label: transfer.from_label.as_deref().unwrap_or_default(),
+        to: format!("{:?}", transfer.to),
+        to_label: transfer.to_label.as_deref().unwrap_or_default(),
+        amount_usd: transfer.amount_usd,
+        status: transfer.status.as_str(),
+    }
+}
+
+fn print_transfers_table(out: &mut impl Write, transfers: &[WhaleTransfer]) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:<16}  {:<8}  {:>22}  {:<40}  {:<40}  TX",
+        "TIME (UTC)", "CHAIN", "AMOUNT", "FROM", "TO"
+    )?;
+    for transfer in transfers {
+        let time = transfer
+            .block_timestamp
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string());
+        writeln!(
+            out,
+            "{:<16}  {:<8}  {:>22}  {:<40}  {:<40}  {}",
+            time,
+            transfer.chain.name(),
+            transfer.formatted_amount(),
+            truncate(&transfer.formatted_from(), 40),
+            truncate(&transfer.formatted_to(), 40),
+            transfer.short_tx_hash()
+        )?;
+    }
+    writeln!(out, "{} transfers", transfers.len())
+}
+
+fn print_totals_table(out: &mut impl Write, totals: &[DailyTotal]) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:<10}  {:<8}  {:>6}  {:>22}",
+        "DAY", "CHAIN", "COUNT", "VOLUME (USD)"
+    )?;
+    for total in totals {
+        writeln!(
+            out,
+            "{:<10}  {:<8}  {:>6}  {:>22}",
+            total.day.to_string(),
+            total.chain.name(),
+            total.count,
+            format!("${}", format_with_commas(total.total_usd))
+        )?;
+    }
+    Ok(())
+}
+
+/// Cut a string to `width` characters, marking the cut with an ellipsis
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width - 1).collect();
+    cut.push('…');
+    cut
+}
@@ -0,0 +1,344 @@
+use crate::config::{AppConfig, USDC_DECIMALS};
+use crate::labels::{self, LabelStore};
+use crate::notify::{self, Dispatcher};
+use crate::template::Templates;
+
+use eyre::Result;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::sync::Notify;
+
+/// Config sections that are only read at startup
+const RESTART_SECTIONS: &[&str] = &[
+    "history", "api", "metrics", "publish", "fx", "language", "classify", "ens",
+];
+
+/// Templates used by the console output, which is set up once at startup
+const RESTART_TEMPLATES: &[&str] = &["console", "banner"];
+
+/// Labels and settings shared with the monitors that can change while they run
+#[derive(Debug)]
+pub struct Live {
+    labels: RwLock<Arc<LabelStore>>,
+    threshold_usd: AtomicU64,
+}
+
+impl Live {
+    pub fn new(labels: LabelStore, threshold_usd: u64) -> Arc<Self> {
+        Arc::new(Self {
+            labels: RwLock::new(Arc::new(labels)),
+            threshold_usd: AtomicU64::new(threshold_usd),
+        })
+    }
+
+    /// Current label store; hold on to it for a consistent view
+    pub fn labels(&self) -> Arc<LabelStore> {
+        Arc::clone(&self.labels.read().expect("labels lock poisoned"))
+    }
+
+    /// Swap in a new label store
+    pub fn set_labels(&self, labels: LabelStore) {
+        *self.labels.write().expect("labels lock poisoned") = Arc::new(labels);
+    }
+
+    /// Minimum transfer size in USD
+    pub fn threshold_usd(&self) -> u64 {
+        self.threshold_usd.load(Ordering::Relaxed)
+    }
+
+    /// Minimum transfer size in raw USDC units
+    pub fn threshold_raw(&self) -> u128 {
+        self.threshold_usd() as u128 * 10u128.pow(USDC_DECIMALS as u32)
+    }
+
+    pub fn set_threshold_usd(&self, threshold_usd: u64) {
+        self.threshold_usd.store(threshold_usd, Ordering::Relaxed);
+    }
+}
+
+/// Reloads the config and label files on SIGHUP or when they change
+///
+/// Labels, the threshold, chat notifiers and `[reload]` itself are applied
+/// live. Changes to webhooks, to sections in [`RESTART_SECTIONS`] and to
+/// the [`RESTART_TEMPLATES`] are logged and need a restart. A config or
+/// label file that fails to load leaves everything as it was.
+pub struct Reloader {
+    config_path: Option<PathBuf>,
+    config: AppConfig,
+    /// Raw TOML of the applied config, to tell which sections changed
+    raw: toml::Table,
+    live: Arc<Live>,
+    dispatcher: Arc<Dispatcher>,
+    modified: BTreeMap<PathBuf, Option<SystemTime>>,
+}
+
+impl Reloader {
+    pub fn new(
+        config_path: Option<PathBuf>,
+        config: AppConfig,
+        live: Arc<Live>,
+        dispatcher: Arc<Dispatcher>,
+    ) -> Result<Self> {
+        let raw = match &config_path {
+            Some(path) => toml::from_str(&fs::read_to_string(path)?)?,
+            None => toml::Table::new(),
+        };
+        let mut reloader = Self {
+            config_path,
+            config,
+            raw,
+            live,
+            dispatcher,
+            modified: BTreeMap::new(),
+        };
+        reloader.modified = reloader.snapshot();
+        Ok(reloader)
+    }
+
+    /// Watch for changes and SIGHUP in the background
+    pub fn spawn(mut self) -> Result<()> {
+        let trigger = Arc::new(Notify::new());
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            let mut hangup = signal(SignalKind::hangup())?;
+            let trigger = Arc::clone(&trigger);
+            tokio::spawn(async move {
+                while hangup.recv().await.is_some() {
+                    tracing::info!("SIGHUP received, reloading");
+                    trigger.notify_one();
+                }
+            });
+        }
+
+        tokio::spawn(async move {
+            let mut interval_secs = self.config.reload.interval_secs;
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs.max(1)));
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        if !self.config.reload.watch || !self.files_changed() {
+                            continue;
+                        }
+                        tracing::info!("Config or label files changed, reloading");
+                    }
+                    _ = trigger.notified() => {}
+                }
+                self.reload();
+                if self.config.reload.interval_secs != interval_secs {
+                    interval_secs = self.config.reload.interval_secs;
+                    interval = tokio::time::interval(Duration::from_secs(interval_secs.max(1)));
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Files whose changes trigger a reload
+    fn watched_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self.config_path.iter().cloned().collect();
+        files.extend(labels::standard_files());
+        files.extend(self.config.labels.files.iter().cloned());
+        files
+    }
+
+    fn snapshot(&self) -> BTreeMap<PathBuf, Option<SystemTime>> {
+        self.watched_files()
+            .into_iter()
+            .map(|path| {
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+                (path, modified)
+            })
+            .collect()
+    }
+
+    /// Check modification times, remembering the new ones
+    fn files_changed(&mut self) -> bool {
+        let snapshot = self.snapshot();
+        let changed = snapshot != self.modified;
+        self.modified = snapshot;
+        changed
+    }
+
+    /// Reload everything, logging what was applied
+    pub fn reload(&mut self) {
+        match self.try_reload() {
+            Ok(changes) if changes.is_empty() => tracing::info!("Reload complete, nothing changed"),
+            Ok(changes) => {
+                for change in changes {
+                    tracing::info!("Reloaded: {}", change);
+                }
+            }
+            Err(e) => tracing::error!(error = %e, "Reload failed, keeping the current config"),
+        }
+        self.modified = self.snapshot();
+    }
+
+    /// Load and apply the config and labels, returning a summary of the changes
+    fn try_reload(&mut self) -> Result<Vec<String>> {
+        let (config, raw) = match &self.config_path {
+            Some(path) => {
+                let content = fs::read_to_string(path)?;
+                let config: AppConfig = toml::from_str(&content)?;
+                (config, toml::from_str::<toml::Table>(&content)?)
+            }
+            None => (self.config.clone(), self.raw.clone()),
+        };
+
+        // Build everything first so a bad file changes nothing
+        let layered = LabelStore::load_layered(&config.labels)?;
+        let notifiers = if section(&raw, "notify", true) != section(&self.raw, "notify", true)
+            || section(&raw, "templates", false) != section(&self.raw, "templates", false)
+        {
+            let templates = Arc::new(Templates::new(&config.templates)?);
+            Some(notify::build_notifiers(&config.notify, &templates)?)
+        } else {
+            None
+        };
+
+        let mut changes = Vec::new();
+        for conflict in &layered.conflicts {
+            tracing::warn!("Label conflict: {}", conflict);
+        }
+        changes.push(format!("{} address labels", layered.store.len()));
+        self.live.set_labels(layered.store);
+
+        if config.threshold_usd() != self.live.threshold_usd() {
+            changes.push(format!(
+                "threshold ${} -> ${}",
+                self.live.threshold_usd(),
+                config.threshold_usd()
+            ));
+            self.live.set_threshold_usd(config.threshold_usd());
+        }
+        if let Some(notifiers) = notifiers {
+            changes.push(format!("{} notification channels", notifiers.len()));
+            self.dispatcher.replace_notifiers(notifiers);
+        }
+
+        if config.reload != self.config.reload {
+            changes.push(format!(
+                "file watching {}, every {}s",
+                if config.reload.watch { "on" } else { "off" },
+                config.reload.interval_secs.max(1)
+            ));
+        }
+
+        for name in restart_required(&self.raw, &raw) {
+            tracing::warn!("Changes to {} take effect after a restart", name);
+        }
+
+        self.config = config;
+        self.raw = raw;
+        Ok(changes)
+    }
+}
+
+/// Config parts that changed but are only applied at startup
+fn restart_required(old: &toml::Table, new: &toml::Table) -> Vec<String> {
+    let mut names: Vec<String> = RESTART_SECTIONS
+        .iter()
+        .filter(|name| section(old, name, false) != section(new, name, false))
+        .map(|name| format!("[{}]", name))
+        .collect();
+    names.extend(
+        RESTART_TEMPLATES
+            .iter()
+            .filter(|name| template(old, name) != template(new, name))
+            .map(|name| format!("templates.{}", name)),
+    );
+    if webhooks(old) != webhooks(new) {
+        names.push("[[notify.webhook]]".to_string());
+    }
+    names
+}
+
+/// A top-level config value, optionally without its webhooks
+fn section(raw: &toml::Table, name: &str, skip_webhooks: bool) -> Option<toml::Value> {
+    let mut value = raw.get(name).cloned()?;
+    if skip_webhooks {
+        if let Some(table) = value.as_table_mut() {
+            table.remove("webhook");
+        }
+    }
+    Some(value)
+}
+
+fn webhooks(raw: &toml::Table) -> Option<&toml::Value> {
+    raw.get("notify")?.get("webhook")
+}
+
+fn template<'a>(raw: &'a toml::Table, name: &str) -> Option<&'a toml::Value> {
+    raw.get("templates")?.get(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Chain;
+    use alloy::primitives::Address;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_reload_swaps_labels_and_threshold() {
+        let dir = tempfile::tempdir().unwrap();
+        let labels_path = dir.path().join("labels.json");
+        let config_path = dir.path().join("config.toml");
+        let address = Address::from_str("0x28C6c06298d514Db089934071355E5743bf21d60").unwrap();
+
+        fs::write(&labels_path, format!(r#"{{"{}": "Before"}}"#, address)).unwrap();
+        let toml = format!(
+            "threshold_usd = 2000000\n[labels]\nembedded = false\nfiles = [{:?}]\n",
+            labels_path
+        );
+        fs::write(&config_path, &toml).unwrap();
+
+        let config = AppConfig::load_from_file(&config_path).unwrap();
+        let layered = LabelStore::load_layered(&config.labels).unwrap();
+        let live = Live::new(layered.store, config.threshold_usd());
+        let dispatcher = Arc::new(Dispatcher::spawn(Vec::new(), Vec::new()));
+        let mut reloader =
+            Reloader::new(Some(config_path.clone()), config, Arc::clone(&live), dispatcher).unwrap();
+        let before = live.labels();
+        assert_eq!(before.get(Chain::Base, &address).as_deref(), Some("Before"));
+        assert!(!reloader.files_changed());
+
+        fs::write(&labels_path, format!(r#"{{"{}": "After"}}"#, address)).unwrap();
+        fs::write(&config_path, toml.replace("2000000", "5000000")).unwrap();
+        reloader.reload();
+
+        assert_eq!(live.labels().get(Chain::Base, &address).as_deref(), Some("After"));
+        assert_eq!(live.threshold_raw(), 5_000_000_000_000);
+        // Readers holding the old store keep a consistent view
+        assert_eq!(before.get(Chain::Base, &address).as_deref(), Some("Before"));
+
+        // A broken config keeps the last good state
+        fs::write(&config_path, "threshold_usd = \"lots\"").unwrap();
+        reloader.reload();
+        assert_eq!(live.threshold_usd(), 5_000_000);
+    }
+
+    #[test]
+    fn test_reports_startup_only_changes() {
+        let old: toml::Table = toml::from_str(
+            "language = \"en\"\n[templates]\nconsole = \"a\"\nslack = \"a\"\n[ens]\nenabled = true\n",
+        )
+        .unwrap();
+        let new: toml::Table = toml::from_str(
+            "language = \"ko\"\n[templates]\nconsole = \"b\"\nslack = \"b\"\n[reload]\ninterval_secs = 5\n\
+             [classify]\nenabled = false\n[ens]\nenabled = false\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            restart_required(&old, &new),
+            ["[language]", "[classify]", "[ens]", "templates.console"]
+        );
+        assert!(restart_required(&new, &new).is_empty());
+    }
+}
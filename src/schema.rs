@@ -0,0 +1,200 @@
+use crate::classify::AddressKind;
+use crate::config::USDC_DECIMALS;
+use crate::labels::LabelCategory;
+use crate::types::{Chain, FiatAmount, TransferStatus, WhaleTransfer};
+
+use alloy::primitives::utils::format_units;
+use alloy::primitives::{Address, B256, U256};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Current version of the serialized transfer schema
+///
+/// Bump this whenever a field is removed or changes meaning. Adding new
+/// optional fields does not require a bump.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Serialized form of a [`WhaleTransfer`] (schema version 1)
+///
+/// ```json
+/// {
+///   "schema_version": 1,
+///   "chain": "ethereum",
+///   "chain_id": 1,
+///   "tx_hash": "0x…",
+///   "block_number": 21000000,
+///   "log_index": 17,
+///   "block_timestamp": "2024-12-08T06:30:45Z",
+///   "status": "confirmed",
+///   "from": "0x…",
+///   "from_label": "Binance Hot Wallet 14",
+///   "from_category": "cex",
+///   "to": "0x…",
+///   "to_label": null,
+///   "to_kind": "safe",
+///   "to_ens": "treasury.eth",
+///   "amount_raw": "1500000000000",
+///   "amount": "1500000.000000",
+///   "explorer_url": "https://etherscan.io/tx/0x…",
+///   "fiat": [{ "currency": "KRW", "amount": 2070000000.0 }]
+/// }
+/// ```
+///
+/// Addresses and hashes are `0x`-prefixed hex strings. Amounts are decimal
+/// strings so no precision is lost: `amount_raw` is the integer token amount
+/// and `amount` is the same value scaled by the USDC decimals.
+/// `block_timestamp` is RFC 3339 in UTC, or `null` when unknown. `status` is
+/// `confirmed`, or `removed` if the log was dropped by a reorg. `fiat` is only
+/// present when FX conversion is configured and is for display only.
+/// `from_category` and `to_category` are one of `cex`, `defi`, `bridge`,
+/// `market_maker`, `issuer` or `fund`, and are omitted when unknown.
+/// `from_kind` and `to_kind` are one of `eoa`, `safe`, `clone` or
+/// `contract`, and are only present for unlabeled addresses that were
+/// classified by their code. `from_ens` and `to_ens` are verified ENS
+/// primary names of unlabeled Ethereum addresses, omitted when there is none.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferRecord {
+    pub schema_version: u32,
+    pub chain: Chain,
+    pub chain_id: u64,
+    pub tx_hash: B256,
+    pub block_number: u64,
+    #[serde(default)]
+    pub log_index: u64,
+    #[serde(default)]
+    pub block_timestamp: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub status: TransferStatus,
+    pub from: Address,
+    pub from_label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_category: Option<LabelCategory>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_kind: Option<AddressKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_ens: Option<String>,
+    pub to: Address,
+    pub to_label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_category: Option<LabelCategory>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_kind: Option<AddressKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_ens: Option<String>,
+    pub amount_raw: String,
+    pub amount: String,
+    pub explorer_url: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fiat: Vec<FiatAmount>,
+}
+
+impl From<WhaleTransfer> for TransferRecord {
+    fn from(transfer: WhaleTransfer) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            chain: transfer.chain,
+            chain_id: transfer.chain.chain_id(),
+            tx_hash: transfer.tx_hash,
+            block_number: transfer.block_number,
+            log_index: transfer.log_index,
+            block_timestamp: transfer.block_timestamp,
+            status: transfer.status,
+            from: transfer.from,
+            from_label: transfer.from_label,
+            from_category: transfer.from_category,
+            from_kind: transfer.from_kind,
+            from_ens: transfer.from_ens,
+            to: transfer.to,
+            to_label: transfer.to_label,
+            to_category: transfer.to_category,
+            to_kind: transfer.to_kind,
+            to_ens: transfer.to_ens,
+            amount_raw: transfer.amount_raw.to_string(),
+            amount: format_units(transfer.amount_raw, USDC_DECIMALS)
+                .unwrap_or_else(|_| transfer.amount_raw.to_string()),
+            explorer_url: transfer.chain.explorer_tx_url(&transfer.tx_hash),
+            fiat: transfer.fiat,
+        }
+    }
+}
+
+impl TryFrom<TransferRecord> for WhaleTransfer {
+    type Error = String;
+
+    fn try_from(record: TransferRecord) -> Result<Self, Self::Error> {
+        if record.schema_version > SCHEMA_VERSION {
+            return Err(format!(
+                "unsupported schema version {} (max {})",
+                record.schema_version, SCHEMA_VERSION
+            ));
+        }
+
+        let amount_raw = U256::from_str(&record.amount_raw)
+            .map_err(|e| format!("invalid amount_raw {:?}: {}", record.amount_raw, e))?;
+
+        Ok(WhaleTransfer::new(
+            record.chain,
+            record.tx_hash,
+            record.block_number,
+            record.from,
+            record.to,
+            amount_raw,
+        )
+        .with_log_index(record.log_index)
+        .with_block_timestamp(record.block_timestamp)
+        .with_status(record.status)
+        .with_from_label(record.from_label)
+        .with_to_label(record.to_label)
+        .with_from_category(record.from_category)
+        .with_to_category(record.to_category)
+        .with_from_kind(record.from_kind)
+        .with_to_kind(record.to_kind)
+        .with_from_ens(record.from_ens)
+        .with_to_ens(record.to_ens)
+        .with_fiat(record.fiat))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let transfer = WhaleTransfer::new(
+            Chain::Base,
+            B256::repeat_byte(0xab),
+            123,
+            Address::repeat_byte(0x11),
+            Address::repeat_byte(0x22),
+            U256::from(2_500_000_123_456u64),
+        )
+        .with_from_label(Some("Coinbase 1".to_string()))
+        .with_from_category(Some(LabelCategory::Cex))
+        .with_to_kind(Some(AddressKind::Safe))
+        .with_to_ens(Some("treasury.eth".to_string()));
+
+        let json = serde_json::to_value(&transfer).unwrap();
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["chain"], "base");
+        assert_eq!(json["chain_id"], 8453);
+        assert_eq!(json["amount_raw"], "2500000123456");
+        assert_eq!(json["amount"], "2500000.123456");
+        assert_eq!(json["to_label"], serde_json::Value::Null);
+        assert_eq!(json["from_category"], "cex");
+        assert!(json.get("to_category").is_none());
+        assert_eq!(json["to_kind"], "safe");
+        assert!(json.get("from_kind").is_none());
+        assert_eq!(json["to_ens"], "treasury.eth");
+        assert!(json.get("fiat").is_none());
+
+        let decoded: WhaleTransfer = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.amount_raw, transfer.amount_raw);
+        assert_eq!(decoded.from_label, transfer.from_label);
+        assert_eq!(decoded.from_category, Some(LabelCategory::Cex));
+        assert_eq!(decoded.to_kind, Some(AddressKind::Safe));
+        assert_eq!(decoded.to_ens.as_deref(), Some("treasury.eth"));
+        assert_eq!(decoded.chain, Chain::Base);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::types::Chain;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// Health of a single chain monitor
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChainStatus {
+    /// Latest block reported by the RPC endpoint
+    pub head_block: Option<u64>,
+    /// Last block whose logs were scanned
+    pub processed_block: Option<u64>,
+    /// When the last poll completed
+    pub last_poll: Option<DateTime<Utc>>,
+    /// Number of RPC errors since startup
+    pub error_count: u64,
+    /// Most recent RPC error
+    pub last_error: Option<String>,
+    /// Number of times the monitor loop restarted
+    pub restarts: u64,
+}
+
+impl ChainStatus {
+    /// Blocks between the head and the last processed block
+    #[cfg_attr(not(any(feature = "api", feature = "tui")), allow(dead_code))]
+    pub fn lag(&self) -> Option<u64> {
+        Some(self.head_block?.saturating_sub(self.processed_block?))
+    }
+}
+
+/// Shared status of all chain monitors, written by [`crate::monitor::ChainMonitor`]
+#[derive(Debug, Default)]
+pub struct StatusRegistry {
+    chains: RwLock<BTreeMap<Chain, ChainStatus>>,
+}
+
+impl StatusRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the status of a chain
+    pub fn update(&self, chain: Chain, f: impl FnOnce(&mut ChainStatus)) {
+        let mut chains = self.chains.write().expect("status lock poisoned");
+        f(chains.entry(chain).or_default());
+    }
+
+    /// Record an RPC error for a chain
+    pub fn record_error(&self, chain: Chain, error: &dyn std::fmt::Display) {
+        self.update(chain, |status| {
+            status.error_count += 1;
+            status.last_error = Some(error.to_string());
+        });
+    }
+
+    /// Copy of the current status of every chain
+    #[cfg_attr(not(any(feature = "api", feature = "tui")), allow(dead_code))]
+    pub fn snapshot(&self) -> BTreeMap<Chain, ChainStatus> {
+        self.chains.read().expect("status lock poisoned").clone()
+    }
+}
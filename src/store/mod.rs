@@ -0,0 +1,61 @@
+#[cfg(feature = "postgres")]
+mod postgres;
+mod sqlite;
+
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresSink;
+pub use sqlite::SqliteStore;
+
+use crate::types::Chain;
+
+use alloy::primitives::Address;
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::ValueEnum;
+
+/// Filters, ordering and paging for reading stored transfers
+#[derive(Debug, Clone, Default)]
+pub struct TransferQuery {
+    /// Only these chains (empty means all chains)
+    pub chains: Vec<Chain>,
+    /// Transfers where this address is the sender or recipient
+    pub address: Option<Address>,
+    /// Case-insensitive substring of the sender or recipient label
+    pub label: Option<String>,
+    /// Minimum amount in USD
+    pub min_amount_usd: Option<f64>,
+    /// Maximum amount in USD
+    pub max_amount_usd: Option<f64>,
+    /// Earliest block time (inclusive)
+    pub since: Option<DateTime<Utc>>,
+    /// Latest block time (exclusive)
+    pub until: Option<DateTime<Utc>>,
+    /// Column to order by
+    pub sort: SortKey,
+    /// Order ascending instead of descending
+    pub ascending: bool,
+    /// Maximum number of rows
+    pub limit: Option<usize>,
+    /// Number of rows to skip
+    pub offset: usize,
+}
+
+/// Ordering for stored transfers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SortKey {
+    /// Block time
+    #[default]
+    Time,
+    /// Transfer amount
+    Amount,
+    /// Block number
+    Block,
+}
+
+/// Transfer count and volume for one chain on one day
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DailyTotal {
+    pub day: NaiveDate,
+    pub chain: Chain,
+    pub count: u64,
+    pub total_usd: f64,
+}
@@ -0,0 +1,436 @@
+use crate::config::PostgresConfig;
+use crate::filter::TransferFilter;
+use crate::notify::{DiskQueue, Notifier};
+use crate::types::WhaleTransfer;
+
+use async_trait::async_trait;
+use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod};
+use eyre::{bail, Result};
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::{sleep, timeout_at, Instant};
+use tokio_postgres::NoTls;
+
+/// Schema migrations, applied in order and recorded in `whale_schema_migrations`
+///
+/// The primary key includes `block_time` because TimescaleDB requires the
+/// partitioning column in every unique index. A transfer's block time is
+/// fixed by its block, so as long as it is the real block time this is still
+/// one row per `(chain, tx_hash, log_index)`. Transfers whose block time is
+/// unknown are rejected rather than stored under a made-up time.
+const MIGRATIONS: &[&str] = &[
+    // 1: transfers table
+    "CREATE TABLE whale_transfers (
+        chain           TEXT           NOT NULL,
+        chain_id        BIGINT         NOT NULL,
+        tx_hash         TEXT           NOT NULL,
+        log_index       BIGINT         NOT NULL,
+        block_number    BIGINT         NOT NULL,
+        block_time      TIMESTAMPTZ    NOT NULL,
+        from_address    TEXT           NOT NULL,
+        from_label      TEXT,
+        to_address      TEXT           NOT NULL,
+        to_label        TEXT,
+        amount_raw      NUMERIC(78, 0) NOT NULL,
+        amount_usd      DOUBLE PRECISION NOT NULL,
+        status          TEXT           NOT NULL,
+        detected_at     TIMESTAMPTZ    NOT NULL DEFAULT now(),
+        PRIMARY KEY (chain, tx_hash, log_index, block_time)
+    );
+    CREATE INDEX whale_transfers_chain_block_idx ON whale_transfers (chain, block_number);
+    CREATE INDEX whale_transfers_from_idx ON whale_transfers (from_address);
+    CREATE INDEX whale_transfers_to_idx ON whale_transfers (to_address);
+    CREATE INDEX whale_transfers_time_idx ON whale_transfers (block_time DESC);",
+];
+
+const UPSERT: &str = "INSERT INTO whale_transfers (
+        chain, chain_id, tx_hash, log_index, block_number, block_time,
+        from_address, from_label, to_address, to_label,
+        amount_raw, amount_usd, status
+    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, CAST($11::TEXT AS NUMERIC), $12, $13)
+    ON CONFLICT (chain, tx_hash, log_index, block_time) DO UPDATE SET
+        from_label = EXCLUDED.from_label,
+        to_label = EXCLUDED.to_label,
+        status = EXCLUDED.status";
+
+/// Delay before retrying a batch that failed to write, doubled after each attempt
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Attempts per batch before its rows are spilled to disk
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Writes transfers to PostgreSQL (optionally a TimescaleDB hypertable)
+///
+/// Transfers are buffered and written by a background task in batches of up
+/// to `batch_size` rows, or whatever arrived within `flush_interval_ms`. A
+/// batch that still fails after [`MAX_ATTEMPTS`] is spilled to a
+/// [`DiskQueue`] so an outage never stalls the dispatcher. Spilled rows are
+/// written one at a time after the next successful batch, and rows the
+/// database rejects on their own are moved to its `failed/` directory.
+pub struct PostgresSink {
+    tx: mpsc::Sender<WhaleTransfer>,
+    filter: TransferFilter,
+}
+
+impl PostgresSink {
+    /// Connect, apply migrations and start the writer task
+    pub async fn connect(config: &PostgresConfig) -> Result<Self> {
+        let pool = create_pool(config)?;
+        migrate(&pool, config.timescaledb).await?;
+        let spill_dir = config
+            .spill_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("queue").join("postgres"));
+        let spill = DiskQueue::open(&spill_dir)?;
+
+        let (tx, rx) = mpsc::channel(config.batch_size.max(1) * 4);
+        tokio::spawn(write_batches(
+            pool,
+            spill,
+            rx,
+            config.batch_size.max(1),
+            Duration::from_millis(config.flush_interval_ms),
+        ));
+
+        Ok(Self {
+            tx,
+            filter: TransferFilter::default(),
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for PostgresSink {
+    fn name(&self) -> &str {
+        "postgres"
+    }
+
+    fn filter(&self) -> &TransferFilter {
+        &self.filter
+    }
+
+    fn durable(&self) -> bool {
+        true
+    }
+
+    async fn notify(&self, transfer: &WhaleTransfer) -> Result<()> {
+        if transfer.block_timestamp.is_none() {
+            bail!(
+                "block timestamp of {:?} is unknown, and postgres rows are keyed by block time",
+                transfer.tx_hash
+            );
+        }
+        self.tx.send(transfer.clone()).await?;
+        Ok(())
+    }
+}
+
+/// Create a connection pool from the config
+fn create_pool(config: &PostgresConfig) -> Result<Pool> {
+    let pg_config = tokio_postgres::Config::from_str(&config.url)?;
+    let manager = Manager::from_config(
+        pg_config,
+        NoTls,
+        ManagerConfig {
+            recycling_method: RecyclingMethod::Fast,
+        },
+    );
+    Ok(Pool::builder(manager).max_size(config.pool_size).build()?)
+}
+
+/// Apply pending migrations and, if requested, convert the table to a hypertable
+async fn migrate(pool: &Pool, timescaledb: bool) -> Result<()> {
+    let mut client = pool.get().await?;
+    client
+        .batch_execute(
+            "CREATE TABLE IF NOT EXISTS whale_schema_migrations (
+                version    INTEGER     PRIMARY KEY,
+                applied_at TIMESTAMPTZ NOT NULL DEFAULT now()
+            )",
+        )
+        .await?;
+
+    let row = client
+        .query_one("SELECT COALESCE(MAX(version), 0) FROM whale_schema_migrations", &[])
+        .await?;
+    let version = row.get::<_, i32>(0) as usize;
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = client.transaction().await?;
+        tx.batch_execute(migration).await?;
+        tx.execute(
+            "INSERT INTO whale_schema_migrations (version) VALUES ($1)",
+            &[&((index + 1) as i32)],
+        )
+        .await?;
+        tx.commit().await?;
+        tracing::info!(version = index + 1, "Applied postgres migration");
+    }
+
+    if timescaledb {
+        client
+            .batch_execute(
+                "CREATE EXTENSION IF NOT EXISTS timescaledb;
+                 SELECT create_hypertable('whale_transfers', 'block_time',
+                     if_not_exists => TRUE, migrate_data => TRUE);",
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Collect transfers into batches and write each batch in one transaction
+async fn write_batches(
+    pool: Pool,
+    spill: DiskQueue,
+    mut rx: mpsc::Receiver<WhaleTransfer>,
+    batch_size: usize,
+    flush_interval: Duration,
+) {
+    let mut batch = Vec::with_capacity(batch_size);
+    // Rows left over from a previous run
+    replay(&pool, &spill).await;
+
+    loop {
+        // Wait for the first transfer, then gather more until full or the interval passes
+        match rx.recv().await {
+            Some(transfer) => batch.push(transfer),
+            None => return,
+        }
+        let deadline = Instant::now() + flush_interval;
+        while batch.len() < batch_size {
+            match timeout_at(deadline, rx.recv()).await {
+                Ok(Some(transfer)) => batch.push(transfer),
+                Ok(None) | Err(_) => break,
+            }
+        }
+
+        if write_with_retries(&pool, &batch).await {
+            replay(&pool, &spill).await;
+        } else {
+            spill_batch(&spill, &batch);
+        }
+        batch.clear();
+    }
+}
+
+/// Write a batch, retrying with backoff; `false` if every attempt failed
+async fn write_with_retries(pool: &Pool, batch: &[WhaleTransfer]) -> bool {
+    let mut delay = RETRY_DELAY;
+    for attempt in 1..=MAX_ATTEMPTS {
+        match write_batch(pool, batch).await {
+            Ok(()) => return true,
+            Err(e) => {
+                tracing::warn!(attempt, error = %e, rows = batch.len(), "Failed to write postgres batch");
+            }
+        }
+        if attempt < MAX_ATTEMPTS {
+            sleep(delay).await;
+            delay *= 2;
+        }
+    }
+    false
+}
+
+/// Queue the rows of a failed batch on disk, one entry per row
+fn spill_batch(spill: &DiskQueue, batch: &[WhaleTransfer]) {
+    tracing::error!(rows = batch.len(), "Spilling postgres batch to disk");
+    for transfer in batch {
+        let result = serde_json::to_vec(transfer)
+            .map_err(eyre::Report::from)
+            .and_then(|body| spill.push(&body));
+        if let Err(e) = result {
+            tracing::error!(tx = ?transfer.tx_hash, error = %e, "Failed to spill postgres row, dropping it");
+        }
+    }
+}
+
+/// Write spilled rows one at a time, moving the ones postgres rejects to `failed/`
+async fn replay(pool: &Pool, spill: &DiskQueue) {
+    let pending = match spill.pending() {
+        Ok(pending) => pending,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to read postgres spill queue");
+            return;
+        }
+    };
+
+    for path in pending {
+        let result = match read_spilled(&path) {
+            Ok(transfer) => write_batch(pool, &[transfer]).await,
+            Err(e) => Err(e),
+        };
+        let result = match result {
+            Ok(()) => spill.remove(&path),
+            // Leave the rest for the next successful batch
+            Err(_) if !reachable(pool).await => return,
+            Err(e) => {
+                tracing::error!(path = %path.display(), error = %e, "Postgres rejected spilled row");
+                spill.fail(&path)
+            }
+        };
+        if let Err(e) = result {
+            tracing::error!(error = %e, "Failed to update postgres spill queue");
+        }
+    }
+}
+
+fn read_spilled(path: &std::path::Path) -> Result<WhaleTransfer> {
+    Ok(serde_json::from_slice(&fs::read(path)?)?)
+}
+
+/// Whether the database answers at all, to tell an outage from a bad row
+async fn reachable(pool: &Pool) -> bool {
+    match pool.get().await {
+        Ok(client) => client.simple_query("SELECT 1").await.is_ok(),
+        Err(_) => false,
+    }
+}
+
+async fn write_batch(pool: &Pool, batch: &[WhaleTransfer]) -> Result<()> {
+    let mut client = pool.get().await?;
+    let tx = client.transaction().await?;
+    let statement = tx.prepare_cached(UPSERT).await?;
+
+    for transfer in batch {
+        tx.execute(
+            &statement,
+            &[
+                &transfer.chain.name().to_lowercase(),
+                &(transfer.chain.chain_id() as i64),
+                &format!("{:?}", transfer.tx_hash),
+                &(transfer.log_index as i64),
+                &(transfer.block_number as i64),
+                // Checked in `notify`
+                &transfer.block_timestamp,
+                &format!("{:?}", transfer.from),
+                &transfer.from_label,
+                &format!("{:?}", transfer.to),
+                &transfer.to_label,
+                &transfer.amount_raw.to_string(),
+                &transfer.amount_usd,
+                &transfer.status.as_str(),
+            ],
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Chain, TransferStatus};
+    use alloy::primitives::{Address, B256, U256};
+    use chrono::DateTime;
+
+    #[tokio::test]
+    async fn test_rejects_unknown_block_time() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let sink = PostgresSink {
+            tx,
+            filter: TransferFilter::default(),
+        };
+        let transfer = WhaleTransfer::new(
+            Chain::Ethereum,
+            B256::repeat_byte(1),
+            10,
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            U256::from(1u64),
+        );
+
+        assert!(sink.notify(&transfer).await.is_err());
+        assert!(rx.try_recv().is_err());
+
+        let dated = transfer.with_block_timestamp(DateTime::from_timestamp(1_700_000_000, 0));
+        sink.notify(&dated).await.unwrap();
+        assert!(rx.try_recv().is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_spills_batches_while_postgres_is_down() {
+        // Nothing listens on a freshly released port
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let config = PostgresConfig {
+            url: format!("postgres://postgres@127.0.0.1:{}/whales", port),
+            ..PostgresConfig::default()
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let spill = DiskQueue::open(dir.path()).unwrap();
+        let transfer = WhaleTransfer::new(
+            Chain::Base,
+            B256::repeat_byte(1),
+            10,
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            U256::from(1u64),
+        )
+        .with_block_timestamp(DateTime::from_timestamp(1_700_000_000, 0));
+
+        let (tx, rx) = mpsc::channel(4);
+        tx.send(transfer.clone()).await.unwrap();
+        drop(tx);
+        write_batches(create_pool(&config).unwrap(), spill, rx, 10, Duration::ZERO).await;
+
+        let spill = DiskQueue::open(dir.path()).unwrap();
+        let pending = spill.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(read_spilled(&pending[0]).unwrap().tx_hash, transfer.tx_hash);
+    }
+
+    /// Runs against the database in `TEST_DATABASE_URL`, e.g.
+    /// `TEST_DATABASE_URL=postgres://postgres@localhost/whales_test cargo test --features postgres -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    async fn test_upsert_batches() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL not set");
+        let config = PostgresConfig {
+            url,
+            ..PostgresConfig::default()
+        };
+        let pool = create_pool(&config).unwrap();
+        pool.get()
+            .await
+            .unwrap()
+            .batch_execute("DROP TABLE IF EXISTS whale_transfers, whale_schema_migrations")
+            .await
+            .unwrap();
+        migrate(&pool, false).await.unwrap();
+        // Migrations are idempotent
+        migrate(&pool, false).await.unwrap();
+
+        let transfer = WhaleTransfer::new(
+            Chain::Ethereum,
+            B256::repeat_byte(1),
+            10,
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            U256::from(u128::MAX),
+        )
+        .with_log_index(4)
+        .with_block_timestamp(DateTime::from_timestamp(1_700_000_000, 0));
+
+        write_batch(&pool, &[transfer.clone(), transfer.clone()])
+            .await
+            .unwrap();
+        write_batch(&pool, &[transfer.with_status(TransferStatus::Removed)])
+            .await
+            .unwrap();
+
+        let client = pool.get().await.unwrap();
+        let rows = client
+            .query("SELECT amount_raw::TEXT, status FROM whale_transfers", &[])
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get::<_, String>(0), u128::MAX.to_string());
+        assert_eq!(rows[0].get::<_, String>(1), "removed");
+    }
+}
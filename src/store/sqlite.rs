@@ -0,0 +1,397 @@
+use super::{DailyTotal, SortKey, TransferQuery};
+use crate::filter::TransferFilter;
+use crate::notify::Notifier;
+use crate::types::{Chain, TransferStatus, WhaleTransfer};
+
+use alloy::primitives::{Address, B256, U256};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use eyre::Result;
+use rusqlite::types::{ToSql, Type};
+use rusqlite::{params, params_from_iter, Connection, Row};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// Schema migrations, applied in order and tracked with `PRAGMA user_version`
+const MIGRATIONS: &[&str] = &[
+    // 1: transfers table
+    "CREATE TABLE transfers (
+        id              INTEGER PRIMARY KEY,
+        chain           TEXT    NOT NULL,
+        chain_id        INTEGER NOT NULL,
+        tx_hash         TEXT    NOT NULL,
+        log_index       INTEGER NOT NULL,
+        block_number    INTEGER NOT NULL,
+        block_timestamp INTEGER,
+        from_address    TEXT    NOT NULL,
+        from_label      TEXT,
+        to_address      TEXT    NOT NULL,
+        to_label        TEXT,
+        amount_raw      TEXT    NOT NULL,
+        amount_usd      REAL    NOT NULL,
+        status          TEXT    NOT NULL,
+        detected_at     INTEGER NOT NULL,
+        UNIQUE (chain, tx_hash, log_index)
+    );
+    CREATE INDEX idx_transfers_chain_block ON transfers (chain, block_number);
+    CREATE INDEX idx_transfers_from ON transfers (from_address);
+    CREATE INDEX idx_transfers_to ON transfers (to_address);
+    CREATE INDEX idx_transfers_time ON transfers (block_timestamp);",
+];
+
+/// Columns selected when reading transfers, in [`row_to_transfer`] order
+const TRANSFER_COLUMNS: &str = "chain, tx_hash, log_index, block_number, block_timestamp, \
+     from_address, from_label, to_address, to_label, amount_raw, status";
+
+/// Time used for filtering and grouping, falling back to detection time
+const TIME_EXPR: &str = "COALESCE(block_timestamp, detected_at)";
+
+/// Local SQLite database of every detected transfer
+#[derive(Clone)]
+pub struct SqliteStore {
+    conn: Arc<Mutex<Connection>>,
+    filter: TransferFilter,
+}
+
+impl SqliteStore {
+    /// Open (or create) the database and apply pending migrations
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Open a private in-memory database
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(mut conn: Connection) -> Result<Self> {
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        migrate(&mut conn)?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            filter: TransferFilter::default(),
+        })
+    }
+
+    /// Insert a transfer, or update its labels and status if it is already stored
+    pub fn insert(&self, transfer: &WhaleTransfer) -> Result<()> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        conn.execute(
+            "INSERT INTO transfers (
+                chain, chain_id, tx_hash, log_index, block_number, block_timestamp,
+                from_address, from_label, to_address, to_label,
+                amount_raw, amount_usd, status, detected_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+            ON CONFLICT (chain, tx_hash, log_index) DO UPDATE SET
+                block_timestamp = COALESCE(excluded.block_timestamp, block_timestamp),
+                from_label = excluded.from_label,
+                to_label = excluded.to_label,
+                status = excluded.status",
+            params![
+                transfer.chain.name().to_lowercase(),
+                transfer.chain.chain_id(),
+                format!("{:?}", transfer.tx_hash),
+                transfer.log_index,
+                transfer.block_number,
+                transfer.block_timestamp.map(|t| t.timestamp()),
+                format!("{:?}", transfer.from),
+                transfer.from_label,
+                format!("{:?}", transfer.to),
+                transfer.to_label,
+                transfer.amount_raw.to_string(),
+                transfer.amount_usd,
+                transfer.status.as_str(),
+                Utc::now().timestamp(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Number of stored transfers
+    pub fn count(&self) -> Result<u64> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        Ok(conn.query_row("SELECT COUNT(*) FROM transfers", [], |row| row.get(0))?)
+    }
+
+    /// Read transfers matching a query
+    pub fn query(&self, query: &TransferQuery) -> Result<Vec<WhaleTransfer>> {
+        let (where_sql, params) = where_clause(query);
+        let order = match query.sort {
+            SortKey::Time => TIME_EXPR,
+            SortKey::Amount => "amount_usd",
+            SortKey::Block => "block_number",
+        };
+        let direction = if query.ascending { "ASC" } else { "DESC" };
+        let sql = format!(
+            "SELECT {} FROM transfers {} ORDER BY {} {}, id {} LIMIT {} OFFSET {}",
+            TRANSFER_COLUMNS,
+            where_sql,
+            order,
+            direction,
+            direction,
+            query.limit.map_or(-1, |l| l as i64),
+            query.offset
+        );
+
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(params.iter()), row_to_transfer)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Count and sum transfers matching a query per UTC day and chain, newest first
+    pub fn daily_totals(&self, query: &TransferQuery) -> Result<Vec<DailyTotal>> {
+        let (where_sql, params) = where_clause(query);
+        let sql = format!(
+            "SELECT date({time}, 'unixepoch') AS day, chain, COUNT(*), SUM(amount_usd)
+             FROM transfers {where_sql}
+             GROUP BY day, chain
+             ORDER BY day DESC, chain",
+            time = TIME_EXPR,
+            where_sql = where_sql
+        );
+
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(params.iter()), |row| {
+            Ok(DailyTotal {
+                day: parse_column::<NaiveDate>(row, 0)?,
+                chain: parse_column(row, 1)?,
+                count: row.get(2)?,
+                total_usd: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+/// Build the WHERE clause and its parameters for a query
+fn where_clause(query: &TransferQuery) -> (String, Vec<Box<dyn ToSql>>) {
+    let mut conditions: Vec<String> = Vec::new();
+    let mut params: Vec<Box<dyn ToSql>> = Vec::new();
+
+    if !query.chains.is_empty() {
+        let placeholders = vec!["?"; query.chains.len()].join(", ");
+        conditions.push(format!("chain IN ({})", placeholders));
+        for chain in &query.chains {
+            params.push(Box::new(chain.name().to_lowercase()));
+        }
+    }
+    if let Some(address) = &query.address {
+        conditions.push("(from_address = ? OR to_address = ?)".to_string());
+        let address = format!("{:?}", address);
+        params.push(Box::new(address.clone()));
+        params.push(Box::new(address));
+    }
+    if let Some(label) = &query.label {
+        conditions.push(r"(from_label LIKE ? ESCAPE '\' OR to_label LIKE ? ESCAPE '\')".to_string());
+        let pattern = format!("%{}%", escape_like(label));
+        params.push(Box::new(pattern.clone()));
+        params.push(Box::new(pattern));
+    }
+    if let Some(min) = query.min_amount_usd {
+        conditions.push("amount_usd >= ?".to_string());
+        params.push(Box::new(min));
+    }
+    if let Some(max) = query.max_amount_usd {
+        conditions.push("amount_usd <= ?".to_string());
+        params.push(Box::new(max));
+    }
+    if let Some(since) = query.since {
+        conditions.push(format!("{} >= ?", TIME_EXPR));
+        params.push(Box::new(since.timestamp()));
+    }
+    if let Some(until) = query.until {
+        conditions.push(format!("{} < ?", TIME_EXPR));
+        params.push(Box::new(until.timestamp()));
+    }
+
+    if conditions.is_empty() {
+        (String::new(), params)
+    } else {
+        (format!("WHERE {}", conditions.join(" AND ")), params)
+    }
+}
+
+/// Read a text column and parse it
+fn parse_column<T: FromStr>(row: &Row<'_>, idx: usize) -> rusqlite::Result<T>
+where
+    T::Err: std::fmt::Display,
+{
+    let value: String = row.get(idx)?;
+    value.parse().map_err(|e: T::Err| {
+        rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, e.to_string().into())
+    })
+}
+
+/// Build a transfer from a row selected with [`TRANSFER_COLUMNS`]
+fn row_to_transfer(row: &Row<'_>) -> rusqlite::Result<WhaleTransfer> {
+    let chain: Chain = parse_column(row, 0)?;
+    let tx_hash: B256 = parse_column(row, 1)?;
+    let from: Address = parse_column(row, 5)?;
+    let to: Address = parse_column(row, 7)?;
+    let amount_raw: U256 = parse_column(row, 9)?;
+    let status: TransferStatus = parse_column(row, 10)?;
+    let timestamp: Option<i64> = row.get(4)?;
+
+    Ok(
+        WhaleTransfer::new(chain, tx_hash, row.get(3)?, from, to, amount_raw)
+            .with_log_index(row.get(2)?)
+            .with_block_timestamp(timestamp.and_then(|t| DateTime::from_timestamp(t, 0)))
+            .with_status(status)
+            .with_from_label(row.get(6)?)
+            .with_to_label(row.get(8)?),
+    )
+}
+
+#[async_trait]
+impl Notifier for SqliteStore {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    fn filter(&self) -> &TransferFilter {
+        &self.filter
+    }
+
+    fn durable(&self) -> bool {
+        true
+    }
+
+    async fn notify(&self, transfer: &WhaleTransfer) -> Result<()> {
+        let store = self.clone();
+        let transfer = transfer.clone();
+        tokio::task::spawn_blocking(move || store.insert(&transfer)).await?
+    }
+}
+
+/// Apply migrations newer than the database's `user_version`
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+        tracing::info!(version = index + 1, "Applied history database migration");
+    }
+
+    Ok(())
+}
+
+/// Escape `LIKE` wildcards so a label filter matches them literally
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer() -> WhaleTransfer {
+        WhaleTransfer::new(
+            Chain::Base,
+            B256::repeat_byte(0xaa),
+            100,
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            U256::from(3_000_000_000_000u64),
+        )
+        .with_log_index(7)
+        .with_block_timestamp(DateTime::from_timestamp(1_700_000_000, 0))
+    }
+
+    #[test]
+    fn test_insert_is_idempotent() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        store.insert(&transfer()).unwrap();
+        store.insert(&transfer()).unwrap();
+        store
+            .insert(&transfer().with_status(TransferStatus::Removed))
+            .unwrap();
+        assert_eq!(store.count().unwrap(), 1);
+
+        let conn = store.conn.lock().unwrap();
+        let (status, log_index, amount_raw, timestamp): (String, u64, String, i64) = conn
+            .query_row(
+                "SELECT status, log_index, amount_raw, block_timestamp FROM transfers",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(status, "removed");
+        assert_eq!(log_index, 7);
+        assert_eq!(amount_raw, "3000000000000");
+        assert_eq!(timestamp, 1_700_000_000);
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let binance = Address::repeat_byte(9);
+        store.insert(&transfer()).unwrap();
+        store
+            .insert(
+                &WhaleTransfer::new(
+                    Chain::Ethereum,
+                    B256::repeat_byte(0xbb),
+                    200,
+                    binance,
+                    Address::repeat_byte(3),
+                    U256::from(9_000_000_000_000u64),
+                )
+                .with_from_label(Some("Binance Hot Wallet 14".to_string()))
+                .with_block_timestamp(DateTime::from_timestamp(1_700_086_400, 0)),
+            )
+            .unwrap();
+
+        let all = store
+            .query(&TransferQuery {
+                sort: SortKey::Amount,
+                ..TransferQuery::default()
+            })
+            .unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].amount_usd, 9_000_000.0);
+
+        let by_label = store
+            .query(&TransferQuery {
+                label: Some("binance".to_string()),
+                ..TransferQuery::default()
+            })
+            .unwrap();
+        assert_eq!(by_label.len(), 1);
+        assert_eq!(by_label[0].from, binance);
+        // Wildcards in the filter are matched literally
+        for label in ["%", "Hot_Wallet"] {
+            let query = TransferQuery {
+                label: Some(label.to_string()),
+                ..TransferQuery::default()
+            };
+            assert!(store.query(&query).unwrap().is_empty());
+        }
+
+        let on_base = TransferQuery {
+            chains: vec![Chain::Base],
+            min_amount_usd: Some(1_000_000.0),
+            ..TransferQuery::default()
+        };
+        assert_eq!(store.query(&on_base).unwrap()[0].log_index, 7);
+
+        let totals = store.daily_totals(&TransferQuery::default()).unwrap();
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[0].chain, Chain::Ethereum);
+        assert_eq!(totals[0].day.to_string(), "2023-11-15");
+    }
+}
@@ -0,0 +1,234 @@
+use crate::i18n;
+use crate::schema::TransferRecord;
+use crate::types::{format_with_commas, Chain, WhaleTransfer};
+
+use alloy::primitives::{Address, B256, U256};
+use colored::Colorize;
+use eyre::{eyre, Result};
+use minijinja::{context, Environment, Error, ErrorKind, UndefinedBehavior, Value};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Template replacing the pretty console block
+pub const CONSOLE: &str = "console";
+
+/// Template replacing the startup banner
+pub const BANNER: &str = "banner";
+
+/// User-defined minijinja templates from the `[templates]` section
+///
+/// Transfer templates see every field of the JSON Lines schema (`chain`,
+/// `tx_hash`, `from_label`, `amount`, `explorer_url`, ...) plus `chain_name`,
+/// `amount_usd`, `from_url`, `to_url`, `fiat_text` and `lang`. Catalog
+/// strings of the selected language are available through `t("amount")`.
+/// The `color` and `bold` filters only style console templates; elsewhere
+/// they pass the text through unchanged.
+pub struct Templates {
+    env: Environment<'static>,
+}
+
+/// A named template that renders transfers
+#[derive(Clone)]
+pub struct Template {
+    templates: Arc<Templates>,
+    name: String,
+}
+
+impl Templates {
+    /// Compile all templates for notifiers; syntax errors are reported here
+    pub fn new(sources: &BTreeMap<String, String>) -> Result<Self> {
+        Self::build(sources, false)
+    }
+
+    /// Compile all templates for the terminal, where `color` and `bold` apply
+    pub fn console(sources: &BTreeMap<String, String>) -> Result<Self> {
+        Self::build(sources, true)
+    }
+
+    fn build(sources: &BTreeMap<String, String>, colors: bool) -> Result<Self> {
+        let mut env = Environment::new();
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+        env.add_filter("usd", |value: f64| format!("${}", format_with_commas(value)));
+        env.add_filter("commas", |value: f64| format_with_commas(value));
+        env.add_filter("amount", |value: f64| i18n::messages().amount(value));
+        env.add_filter("datetime", |value: String| {
+            let datetime = chrono::DateTime::parse_from_rfc3339(&value)
+                .map_err(|e| Error::new(ErrorKind::InvalidOperation, e.to_string()))?;
+            Ok::<_, Error>(i18n::messages().datetime(&datetime.with_timezone(&chrono::Local)))
+        });
+        env.add_function("t", |key: String| {
+            i18n::messages().get(&key).ok_or_else(|| {
+                Error::new(ErrorKind::InvalidOperation, format!("unknown message {:?}", key))
+            })
+        });
+        env.add_global("lang", i18n::messages().lang.code());
+        env.add_filter("short", |value: String| shorten(&value));
+        if colors {
+            env.add_filter("color", |value: String, color: String| {
+                value.color(color.as_str()).to_string()
+            });
+            env.add_filter("bold", |value: String| value.bold().to_string());
+        } else {
+            env.add_filter("color", |value: String, _color: String| value);
+            env.add_filter("bold", |value: String| value);
+        }
+        env.add_function("tx_url", |chain: String, hash: String| {
+            Ok(parse_chain(&chain)?.explorer_tx_url(&parse(&hash)?))
+        });
+        env.add_function("address_url", |chain: String, address: String| {
+            Ok(parse_chain(&chain)?.explorer_address_url(&parse(&address)?))
+        });
+
+        for (name, source) in sources {
+            env.add_template_owned(name.clone(), source.clone())
+                .map_err(|e| eyre!("template {:?}: {:#}", name, e))?;
+        }
+        Ok(Self { env })
+    }
+
+    /// Whether a template with this name is defined
+    pub fn contains(&self, name: &str) -> bool {
+        self.env.get_template(name).is_ok()
+    }
+
+    /// Look up a transfer template, checking that it renders a sample transfer
+    pub fn get(self: &Arc<Self>, name: &str) -> Result<Template> {
+        if !self.contains(name) {
+            eyre::bail!("template {:?} is not defined in [templates]", name);
+        }
+        let template = Template {
+            templates: Arc::clone(self),
+            name: name.to_string(),
+        };
+        template.render(&sample_transfer())?;
+        Ok(template)
+    }
+
+    /// Render the banner template, if one is defined
+    pub fn banner(&self, chains: &[Chain], threshold_usd: u64) -> Result<Option<String>> {
+        if !self.contains(BANNER) {
+            return Ok(None);
+        }
+        let chains: Vec<&str> = chains.iter().map(|c| c.name()).collect();
+        let rendered = self.env.get_template(BANNER)?.render(context! {
+            version => env!("CARGO_PKG_VERSION"),
+            threshold_usd,
+            chains => chains,
+        });
+        Ok(Some(rendered.map_err(|e| eyre!("template {:?}: {:#}", BANNER, e))?))
+    }
+}
+
+impl Template {
+    /// Render a transfer
+    pub fn render(&self, transfer: &WhaleTransfer) -> Result<String> {
+        let mut fields = serde_json::to_value(TransferRecord::from(transfer.clone()))?;
+        if let Some(fields) = fields.as_object_mut() {
+            fields.insert("chain_name".into(), transfer.chain.name().into());
+            fields.insert("amount_usd".into(), transfer.amount_usd.into());
+            fields.insert(
+                "fiat_text".into(),
+                transfer.formatted_fiat().unwrap_or_default().into(),
+            );
+            fields.insert(
+                "from_url".into(),
+                transfer.chain.explorer_address_url(&transfer.from).into(),
+            );
+            fields.insert(
+                "to_url".into(),
+                transfer.chain.explorer_address_url(&transfer.to).into(),
+            );
+        }
+
+        self.templates
+            .env
+            .get_template(&self.name)?
+            .render(Value::from_serialize(&fields))
+            .map_err(|e| eyre!("template {:?}: {:#}", self.name, e))
+    }
+}
+
+/// Shorten a hex address or hash to `0x12345678...9abcdef0`
+fn shorten(value: &str) -> String {
+    if value.len() <= 20 || !value.is_char_boundary(10) || !value.is_char_boundary(value.len() - 8) {
+        return value.to_string();
+    }
+    format!("{}...{}", &value[..10], &value[value.len() - 8..])
+}
+
+fn parse_chain(chain: &str) -> Result<Chain, Error> {
+    Chain::from_str(chain).map_err(|e| Error::new(ErrorKind::InvalidOperation, e.to_string()))
+}
+
+fn parse<T: FromStr>(value: &str) -> Result<T, Error> {
+    value
+        .parse()
+        .map_err(|_| Error::new(ErrorKind::InvalidOperation, format!("invalid hex value {:?}", value)))
+}
+
+/// Transfer used to check templates at startup
+fn sample_transfer() -> WhaleTransfer {
+    WhaleTransfer::new(
+        Chain::Ethereum,
+        B256::repeat_byte(0xab),
+        21_000_000,
+        Address::repeat_byte(0x12),
+        Address::repeat_byte(0x34),
+        U256::from(1_500_000_000_000u64),
+    )
+    .with_from_label(Some("Sample Exchange".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn templates(sources: &[(&str, &str)]) -> Result<Arc<Templates>> {
+        let sources = sources
+            .iter()
+            .map(|(name, source)| (name.to_string(), source.to_string()))
+            .collect();
+        Ok(Arc::new(Templates::new(&sources)?))
+    }
+
+    #[test]
+    fn test_render_transfer() {
+        let templates = templates(&[(
+            "short",
+            "{{ amount_usd | usd }} on {{ chain_name }} {{ from_label or 'Unknown' }} -> {{ to_label or 'Unknown' }} {{ tx_hash | short }} {{ tx_url(chain, tx_hash) }}",
+        )])
+        .unwrap();
+
+        let rendered = templates.get("short").unwrap().render(&sample_transfer()).unwrap();
+        assert_eq!(
+            rendered,
+            "$1,500,000.00 on ETHEREUM Sample Exchange -> Unknown 0xabababab...abababab \
+             https://etherscan.io/tx/0xabababababababababababababababababababababababababababababababab"
+        );
+    }
+
+    #[test]
+    fn test_errors_are_reported_early() {
+        assert!(templates(&[("broken", "{{ amount ")]).is_err());
+
+        let templates = templates(&[("typo", "{{ amont }}")]).unwrap();
+        assert!(templates.get("typo").is_err());
+        assert!(templates.get("missing").is_err());
+        assert_eq!(templates.banner(&[Chain::Base], 1_000_000).unwrap(), None);
+    }
+
+    #[test]
+    fn test_colors_only_in_console_templates() {
+        let sources = [("alert".to_string(), "{{ chain_name | color('red') | bold }}".to_string())]
+            .into_iter()
+            .collect();
+        let _colors = crate::console::COLOR_OVERRIDE.lock().unwrap();
+        colored::control::set_override(true);
+        let plain = Arc::new(Templates::new(&sources).unwrap());
+        let console = Arc::new(Templates::console(&sources).unwrap());
+
+        assert_eq!(plain.get("alert").unwrap().render(&sample_transfer()).unwrap(), "ETHEREUM");
+        assert!(console.get("alert").unwrap().render(&sample_transfer()).unwrap().contains('\x1b'));
+    }
+}
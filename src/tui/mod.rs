@@ -0,0 +1,316 @@
+mod ui;
+
+use crate::notify::Dispatcher;
+use crate::status::StatusRegistry;
+use crate::types::{Chain, TransferStatus, WhaleTransfer};
+
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use eyre::Result;
+use futures::StreamExt;
+use ratatui::DefaultTerminal;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Number of transfers kept in the alert feed
+const FEED_CAPACITY: usize = 1000;
+
+/// Rows moved by PageUp / PageDown
+const PAGE_SIZE: usize = 10;
+
+/// Chains in the order of the `1`-`3` filter keys
+const CHAINS: [Chain; 3] = Chain::ALL;
+
+/// Transfer count and volume
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Totals {
+    pub count: u64,
+    pub volume_usd: f64,
+}
+
+impl Totals {
+    fn add(&mut self, amount_usd: f64) {
+        self.count += 1;
+        self.volume_usd += amount_usd;
+    }
+}
+
+/// Dashboard state, updated by transfers and key presses
+#[derive(Debug, Default)]
+pub struct App {
+    /// Received transfers, newest first
+    feed: VecDeque<WhaleTransfer>,
+    chain_totals: BTreeMap<Chain, Totals>,
+    entity_totals: HashMap<String, Totals>,
+    /// Chains hidden from the feed
+    hidden: BTreeSet<Chain>,
+    /// Case-insensitive label or address filter
+    search: String,
+    /// Whether keys are being typed into `search`
+    editing: bool,
+    /// Rows scrolled back from the newest visible transfer
+    scroll: usize,
+}
+
+impl App {
+    /// Add a detected transfer to the feed and totals
+    pub fn push(&mut self, transfer: WhaleTransfer) {
+        if transfer.status == TransferStatus::Confirmed {
+            self.chain_totals
+                .entry(transfer.chain)
+                .or_default()
+                .add(transfer.amount_usd);
+
+            let mut entities: Vec<&String> =
+                transfer.from_label.iter().chain(&transfer.to_label).collect();
+            entities.dedup();
+            for entity in entities {
+                self.entity_totals
+                    .entry(entity.clone())
+                    .or_default()
+                    .add(transfer.amount_usd);
+            }
+        }
+
+        // Keep the same rows on screen while scrolled back
+        if self.scroll > 0 && self.is_visible(&transfer) {
+            self.scroll += 1;
+        }
+
+        self.feed.push_front(transfer);
+        self.feed.truncate(FEED_CAPACITY);
+    }
+
+    /// Whether a transfer passes the chain and search filters
+    fn is_visible(&self, transfer: &WhaleTransfer) -> bool {
+        if self.hidden.contains(&transfer.chain) {
+            return false;
+        }
+        if self.search.is_empty() {
+            return true;
+        }
+
+        let search = self.search.to_lowercase();
+        let matches_label = |label: &Option<String>| {
+            label
+                .as_ref()
+                .is_some_and(|l| l.to_lowercase().contains(&search))
+        };
+        matches_label(&transfer.from_label)
+            || matches_label(&transfer.to_label)
+            || format!("{:?}", transfer.from).contains(&search)
+            || format!("{:?}", transfer.to).contains(&search)
+    }
+
+    /// Transfers passing the filters, newest first
+    pub fn visible(&self) -> Vec<&WhaleTransfer> {
+        self.feed.iter().filter(|t| self.is_visible(t)).collect()
+    }
+
+    /// Entities with the largest volume
+    pub fn top_entities(&self, limit: usize) -> Vec<(&str, Totals)> {
+        let mut entities: Vec<(&str, Totals)> = self
+            .entity_totals
+            .iter()
+            .map(|(entity, totals)| (entity.as_str(), *totals))
+            .collect();
+        entities.sort_by(|a, b| b.1.volume_usd.total_cmp(&a.1.volume_usd));
+        entities.truncate(limit);
+        entities
+    }
+
+    /// Handle a key press; returns `false` when the dashboard should exit
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return false;
+        }
+
+        if self.editing {
+            match key.code {
+                KeyCode::Char(c) => self.search.push(c),
+                KeyCode::Backspace => {
+                    self.search.pop();
+                }
+                KeyCode::Esc => {
+                    self.search.clear();
+                    self.editing = false;
+                }
+                KeyCode::Enter => self.editing = false,
+                _ => {}
+            }
+            self.scroll = 0;
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_by(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_by(-1),
+            KeyCode::PageUp => self.scroll_by(PAGE_SIZE as isize),
+            KeyCode::PageDown => self.scroll_by(-(PAGE_SIZE as isize)),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::Char('/') => self.editing = true,
+            KeyCode::Char('0') => {
+                self.hidden.clear();
+                self.search.clear();
+                self.scroll = 0;
+            }
+            KeyCode::Char(c @ '1'..='3') => {
+                let chain = CHAINS[c as usize - '1' as usize];
+                if !self.hidden.remove(&chain) {
+                    self.hidden.insert(chain);
+                }
+                self.scroll = 0;
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn scroll_by(&mut self, rows: isize) {
+        let max = self.visible().len().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(rows).min(max);
+    }
+}
+
+/// Run the dashboard until the user quits or all monitors stop
+///
+/// Takes over the printer loop: every transfer is still dispatched to the sinks.
+pub async fn run(
+    rx: mpsc::Receiver<WhaleTransfer>,
+    dispatcher: Arc<Dispatcher>,
+    status: Arc<StatusRegistry>,
+) -> Result<()> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, rx, &dispatcher, &status).await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    mut rx: mpsc::Receiver<WhaleTransfer>,
+    dispatcher: &Dispatcher,
+    status: &StatusRegistry,
+) -> Result<()> {
+    let mut app = App::default();
+    let mut events = EventStream::new();
+    // Redraw periodically so the status panel stays current
+    let mut tick = tokio::time::interval(Duration::from_secs(1));
+
+    loop {
+        let statuses = status.snapshot();
+        terminal.draw(|frame| ui::draw(frame, &app, &statuses))?;
+
+        tokio::select! {
+            transfer = rx.recv() => match transfer {
+                Some(transfer) => {
+                    dispatcher.dispatch(&transfer).await;
+                    app.push(transfer);
+                }
+                None => return Ok(()),
+            },
+            event = events.next() => match event {
+                Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                    if !app.handle_key(key) {
+                        return Ok(());
+                    }
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e.into()),
+                None => return Ok(()),
+            },
+            _ = tick.tick() => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Address, B256, U256};
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn transfer(chain: Chain, amount_usd: u64, from_label: Option<&str>) -> WhaleTransfer {
+        WhaleTransfer::new(
+            chain,
+            B256::repeat_byte(1),
+            100,
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            U256::from(amount_usd * 1_000_000),
+        )
+        .with_from_label(from_label.map(str::to_string))
+    }
+
+    fn press(app: &mut App, code: KeyCode) -> bool {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_totals_and_filters() {
+        let mut app = App::default();
+        app.push(transfer(Chain::Ethereum, 2_000_000, Some("Binance")));
+        app.push(transfer(Chain::Base, 1_000_000, Some("Coinbase")));
+        app.push(transfer(Chain::Ethereum, 3_000_000, None));
+
+        assert_eq!(app.chain_totals[&Chain::Ethereum].count, 2);
+        assert_eq!(app.chain_totals[&Chain::Ethereum].volume_usd, 5_000_000.0);
+        assert_eq!(app.top_entities(1), vec![("Binance", Totals { count: 1, volume_usd: 2_000_000.0 })]);
+
+        // Hide Ethereum
+        assert!(press(&mut app, KeyCode::Char('1')));
+        assert_eq!(app.visible().len(), 1);
+        press(&mut app, KeyCode::Char('0'));
+
+        // Search labels
+        press(&mut app, KeyCode::Char('/'));
+        for c in "binance".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.visible().len(), 1);
+        assert_eq!(app.visible()[0].from_label.as_deref(), Some("Binance"));
+
+        assert!(!press(&mut app, KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn test_scroll_stays_on_rows() {
+        let mut app = App::default();
+        for amount in 1..=5 {
+            app.push(transfer(Chain::Base, amount * 1_000_000, None));
+        }
+
+        press(&mut app, KeyCode::PageUp);
+        assert_eq!(app.scroll, 4);
+        app.push(transfer(Chain::Base, 9_000_000, None));
+        assert_eq!(app.scroll, 5);
+        press(&mut app, KeyCode::Home);
+        assert_eq!(app.scroll, 0);
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut app = App::default();
+        app.push(transfer(Chain::Arbitrum, 4_000_000, Some("Jump Trading")));
+        let statuses = BTreeMap::new();
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal
+            .draw(|frame| ui::draw(frame, &app, &statuses))
+            .unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("$4,000,000"));
+        assert!(screen.contains("Jump Trading"));
+    }
+}
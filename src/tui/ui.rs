@@ -0,0 +1,242 @@
+use super::{App, CHAINS};
+use crate::i18n;
+use crate::status::ChainStatus;
+use crate::types::{format_with_commas, short_address, Chain, TransferStatus};
+
+use chrono::Local;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table};
+use ratatui::Frame;
+use std::collections::BTreeMap;
+
+/// Same colors as the pretty console output
+fn chain_color(chain: Chain) -> Color {
+    match chain {
+        Chain::Ethereum => Color::Blue,
+        Chain::Arbitrum => Color::Cyan,
+        Chain::Base => Color::Magenta,
+    }
+}
+
+fn usd(amount: f64) -> String {
+    format!(
+        "${}",
+        format_with_commas(amount.round()).trim_end_matches(".00")
+    )
+}
+
+fn label_or_address(address: &alloy::primitives::Address, label: &Option<String>) -> String {
+    label.clone().unwrap_or_else(|| short_address(address))
+}
+
+/// Draw the whole dashboard
+pub fn draw(frame: &mut Frame, app: &App, statuses: &BTreeMap<Chain, ChainStatus>) {
+    let [top, middle, footer] = Layout::vertical([
+        Constraint::Length(CHAINS.len() as u16 + 3),
+        Constraint::Min(5),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [status_area, totals_area] =
+        Layout::horizontal([Constraint::Percentage(65), Constraint::Percentage(35)]).areas(top);
+    let [feed_area, entities_area] =
+        Layout::horizontal([Constraint::Percentage(70), Constraint::Percentage(30)]).areas(middle);
+
+    draw_status(frame, status_area, statuses);
+    draw_chain_totals(frame, totals_area, app);
+    draw_feed(frame, feed_area, app);
+    draw_entities(frame, entities_area, app);
+    draw_footer(frame, footer, app);
+}
+
+fn header(cells: &[&'static str]) -> Row<'static> {
+    Row::new(cells.iter().copied()).style(Style::new().bold().fg(Color::White))
+}
+
+fn draw_status(frame: &mut Frame, area: Rect, statuses: &BTreeMap<Chain, ChainStatus>) {
+    let optional = |value: Option<u64>| value.map_or("-".to_string(), |v| v.to_string());
+
+    let rows = CHAINS.iter().map(|chain| {
+        let status = statuses.get(chain).cloned().unwrap_or_default();
+        let lag = status.lag();
+        let lag_style = match lag {
+            Some(0..=5) => Style::new().green(),
+            Some(_) => Style::new().red(),
+            None => Style::new(),
+        };
+        let errors_style = if status.error_count > 0 {
+            Style::new().yellow()
+        } else {
+            Style::new()
+        };
+
+        Row::new(vec![
+            Cell::from(chain.name()).fg(chain_color(*chain)),
+            Cell::from(optional(status.head_block)),
+            Cell::from(optional(status.processed_block)),
+            Cell::from(optional(lag)).style(lag_style),
+            Cell::from(status.error_count.to_string()).style(errors_style),
+            Cell::from(status.last_error.unwrap_or_default()).dark_gray(),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(9),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(5),
+            Constraint::Length(6),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header(&[
+        "Chain",
+        "Head",
+        "Processed",
+        "Lag",
+        "Errors",
+        "Last error",
+    ]))
+    .block(Block::bordered().title(" Monitors "));
+    frame.render_widget(table, area);
+}
+
+fn draw_chain_totals(frame: &mut Frame, area: Rect, app: &App) {
+    let messages = i18n::messages();
+    let rows = CHAINS.iter().map(|chain| {
+        let totals = app.chain_totals.get(chain).copied().unwrap_or_default();
+        Row::new(vec![
+            Cell::from(chain.name()).fg(chain_color(*chain)),
+            Cell::from(totals.count.to_string()),
+            Cell::from(usd(totals.volume_usd)).green(),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(9),
+            Constraint::Length(6),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header(&[messages.chain, messages.count, messages.volume]))
+    .block(Block::bordered().title(format!(" {} ", messages.totals_by_chain)));
+    frame.render_widget(table, area);
+}
+
+fn draw_feed(frame: &mut Frame, area: Rect, app: &App) {
+    let messages = i18n::messages();
+    let visible = app.visible();
+    let rows = visible.iter().skip(app.scroll).map(|transfer| {
+        let time = transfer
+            .block_timestamp
+            .map(|t| t.with_timezone(&Local).format("%H:%M:%S").to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let removed = transfer.status == TransferStatus::Removed;
+
+        let row = Row::new(vec![
+            Cell::from(time).dark_gray(),
+            Cell::from(transfer.chain.name()).fg(chain_color(transfer.chain)),
+            Cell::from(usd(transfer.amount_usd)).green().bold(),
+            Cell::from(label_or_address(&transfer.from, &transfer.from_label)),
+            Cell::from(label_or_address(&transfer.to, &transfer.to_label)),
+            Cell::from(if removed { "removed" } else { "" }).red(),
+        ]);
+        if removed {
+            row.add_modifier(Modifier::CROSSED_OUT)
+        } else {
+            row
+        }
+    });
+
+    let mut title = format!(" Alerts ({}/{}) ", visible.len(), app.feed.len());
+    if app.scroll > 0 {
+        title.push_str(&format!("[scrolled {}] ", app.scroll));
+    }
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Length(9),
+            Constraint::Length(16),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(7),
+        ],
+    )
+    .header(header(&[
+        messages.time,
+        messages.chain,
+        messages.amount,
+        messages.from,
+        messages.to,
+        "",
+    ]))
+    .block(Block::bordered().title(title));
+    frame.render_widget(table, area);
+}
+
+fn draw_entities(frame: &mut Frame, area: Rect, app: &App) {
+    let messages = i18n::messages();
+    let limit = area.height.saturating_sub(3) as usize;
+    let rows = app.top_entities(limit).into_iter().map(|(entity, totals)| {
+        Row::new(vec![
+            Cell::from(entity.to_string()),
+            Cell::from(totals.count.to_string()),
+            Cell::from(usd(totals.volume_usd)).green(),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(1),
+            Constraint::Length(6),
+            Constraint::Length(16),
+        ],
+    )
+    .header(header(&[messages.entity, messages.count, messages.volume]))
+    .block(Block::bordered().title(format!(" {} ", messages.totals_by_entity)));
+    frame.render_widget(table, area);
+}
+
+fn draw_footer(frame: &mut Frame, area: Rect, app: &App) {
+    let line = if app.editing {
+        Line::from(vec![
+            Span::from("Search: ").bold(),
+            Span::from(format!("{}_", app.search)),
+            Span::from("  (Enter to apply, Esc to clear)").dark_gray(),
+        ])
+    } else {
+        let chains: Vec<Span> = CHAINS
+            .iter()
+            .enumerate()
+            .map(|(i, chain)| {
+                let span = Span::from(format!("{}:{} ", i + 1, chain.name()));
+                if app.hidden.contains(chain) {
+                    span.dark_gray().crossed_out()
+                } else {
+                    span.fg(chain_color(*chain))
+                }
+            })
+            .collect();
+
+        let mut spans =
+            vec![
+                Span::from("q quit  ↑↓/PgUp/PgDn scroll  Home newest  / search  0 reset  ")
+                    .dark_gray(),
+            ];
+        spans.extend(chains);
+        if !app.search.is_empty() {
+            spans.push(Span::from(format!(" search: {}", app.search)).yellow());
+        }
+        Line::from(spans)
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
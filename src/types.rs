@@ -1,8 +1,18 @@
+use crate::classify::AddressKind;
+use crate::i18n;
+use crate::labels::LabelCategory;
+use crate::schema::TransferRecord;
+
 use alloy::primitives::{Address, B256, U256};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::fmt;
+use std::str::FromStr;
 
 /// Supported blockchain networks
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Chain {
     Ethereum,
     Arbitrum,
@@ -10,6 +20,9 @@ pub enum Chain {
 }
 
 impl Chain {
+    /// Every supported chain
+    pub const ALL: [Chain; 3] = [Chain::Ethereum, Chain::Arbitrum, Chain::Base];
+
     /// Get the display name of the chain
     pub fn name(&self) -> &'static str {
         match self {
@@ -19,6 +32,25 @@ impl Chain {
         }
     }
 
+    /// Get the EIP-155 chain id
+    pub fn chain_id(&self) -> u64 {
+        match self {
+            Chain::Ethereum => 1,
+            Chain::Arbitrum => 42161,
+            Chain::Base => 8453,
+        }
+    }
+
+    /// Look up a supported chain by its EIP-155 chain id
+    pub fn from_chain_id(chain_id: u64) -> Option<Self> {
+        match chain_id {
+            1 => Some(Chain::Ethereum),
+            42161 => Some(Chain::Arbitrum),
+            8453 => Some(Chain::Base),
+            _ => None,
+        }
+    }
+
     /// Get the block explorer URL for transactions
     pub fn explorer_tx_url(&self, tx_hash: &B256) -> String {
         let base_url = match self {
@@ -46,8 +78,57 @@ impl fmt::Display for Chain {
     }
 }
 
+impl FromStr for Chain {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ethereum" | "eth" => Ok(Chain::Ethereum),
+            "arbitrum" | "arb" => Ok(Chain::Arbitrum),
+            "base" => Ok(Chain::Base),
+            other => Err(format!("unknown chain: {}", other)),
+        }
+    }
+}
+
+/// Whether the log behind a transfer is still part of the canonical chain
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferStatus {
+    /// Included in a block
+    #[default]
+    Confirmed,
+    /// Removed by a chain reorganization
+    Removed,
+}
+
+impl TransferStatus {
+    /// Get the lowercase name used in storage and JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferStatus::Confirmed => "confirmed",
+            TransferStatus::Removed => "removed",
+        }
+    }
+}
+
+impl FromStr for TransferStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "confirmed" => Ok(TransferStatus::Confirmed),
+            "removed" => Ok(TransferStatus::Removed),
+            other => Err(format!("unknown transfer status: {}", other)),
+        }
+    }
+}
+
 /// Represents a detected whale transfer
-#[derive(Debug, Clone)]
+///
+/// Serializes through [`TransferRecord`], see `schema.rs` for the format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "TransferRecord", try_from = "TransferRecord")]
 pub struct WhaleTransfer {
     /// The blockchain where the transfer occurred
     pub chain: Chain,
@@ -55,18 +136,46 @@ pub struct WhaleTransfer {
     pub tx_hash: B256,
     /// Block number
     pub block_number: u64,
+    /// Position of the Transfer log within the block
+    pub log_index: u64,
+    /// Block timestamp (if known)
+    pub block_timestamp: Option<DateTime<Utc>>,
+    /// Whether the log is still canonical
+    pub status: TransferStatus,
     /// Sender address
     pub from: Address,
     /// Sender label (if known)
     pub from_label: Option<String>,
+    /// Sender category (if known)
+    pub from_category: Option<LabelCategory>,
+    /// Sender account kind (if classified)
+    pub from_kind: Option<AddressKind>,
+    /// Sender ENS primary name (if unlabeled and verified)
+    pub from_ens: Option<String>,
     /// Recipient address
     pub to: Address,
     /// Recipient label (if known)
     pub to_label: Option<String>,
+    /// Recipient category (if known)
+    pub to_category: Option<LabelCategory>,
+    /// Recipient account kind (if classified)
+    pub to_kind: Option<AddressKind>,
+    /// Recipient ENS primary name (if unlabeled and verified)
+    pub to_ens: Option<String>,
     /// Transfer amount in raw units (6 decimals for USDC)
     pub amount_raw: U256,
     /// Transfer amount in USD
     pub amount_usd: f64,
+    /// Transfer amount in the configured fiat currencies
+    pub fiat: Vec<FiatAmount>,
+}
+
+/// An amount converted to a fiat currency
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FiatAmount {
+    /// ISO 4217 code, e.g. `KRW`
+    pub currency: String,
+    pub amount: f64,
 }
 
 impl WhaleTransfer {
@@ -86,15 +195,43 @@ impl WhaleTransfer {
             chain,
             tx_hash,
             block_number,
+            log_index: 0,
+            block_timestamp: None,
+            status: TransferStatus::Confirmed,
             from,
             from_label: None,
+            from_category: None,
+            from_kind: None,
+            from_ens: None,
             to,
             to_label: None,
+            to_category: None,
+            to_kind: None,
+            to_ens: None,
             amount_raw,
             amount_usd,
+            fiat: Vec::new(),
         }
     }
 
+    /// Set the log index within the block
+    pub fn with_log_index(mut self, log_index: u64) -> Self {
+        self.log_index = log_index;
+        self
+    }
+
+    /// Set the block timestamp
+    pub fn with_block_timestamp(mut self, timestamp: Option<DateTime<Utc>>) -> Self {
+        self.block_timestamp = timestamp;
+        self
+    }
+
+    /// Set the transfer status
+    pub fn with_status(mut self, status: TransferStatus) -> Self {
+        self.status = status;
+        self
+    }
+
     /// Set the from address label
     pub fn with_from_label(mut self, label: Option<String>) -> Self {
         self.from_label = label;
@@ -107,31 +244,102 @@ impl WhaleTransfer {
         self
     }
 
-    /// Format the address with optional label
-    fn format_address(address: &Address, label: &Option<String>) -> String {
-        let addr_str = format!("{:?}", address);
-        let short_addr = format!("{}...{}", &addr_str[..10], &addr_str[addr_str.len() - 8..]);
+    /// Set the from address category
+    pub fn with_from_category(mut self, category: Option<LabelCategory>) -> Self {
+        self.from_category = category;
+        self
+    }
+
+    /// Set the to address category
+    pub fn with_to_category(mut self, category: Option<LabelCategory>) -> Self {
+        self.to_category = category;
+        self
+    }
+
+    /// Set the from account kind
+    pub fn with_from_kind(mut self, kind: Option<AddressKind>) -> Self {
+        self.from_kind = kind;
+        self
+    }
+
+    /// Set the to account kind
+    pub fn with_to_kind(mut self, kind: Option<AddressKind>) -> Self {
+        self.to_kind = kind;
+        self
+    }
 
-        match label {
-            Some(l) => format!("{} ({})", short_addr, l),
-            None => format!("{} (Unknown)", short_addr),
+    /// Set the from ENS name
+    pub fn with_from_ens(mut self, name: Option<String>) -> Self {
+        self.from_ens = name;
+        self
+    }
+
+    /// Set the to ENS name
+    pub fn with_to_ens(mut self, name: Option<String>) -> Self {
+        self.to_ens = name;
+        self
+    }
+
+    /// Set the fiat conversions
+    pub fn with_fiat(mut self, fiat: Vec<FiatAmount>) -> Self {
+        self.fiat = fiat;
+        self
+    }
+
+    /// Format the address with its label and category, or what is known about it
+    fn format_address(address: &Address, name: &str, category: Option<LabelCategory>) -> String {
+        let short_addr = short_address(address);
+
+        match category {
+            Some(c) => format!("{} ({} · {})", short_addr, name, c),
+            None => format!("{} ({})", short_addr, name),
         }
     }
 
     /// Get formatted from address
     pub fn formatted_from(&self) -> String {
-        Self::format_address(&self.from, &self.from_label)
+        Self::format_address(&self.from, &self.sender_name(), self.from_category)
     }
 
     /// Get formatted to address
     pub fn formatted_to(&self) -> String {
-        Self::format_address(&self.to, &self.to_label)
+        Self::format_address(&self.to, &self.recipient_name(), self.to_category)
+    }
+
+    /// Sender label, or its ENS name or kind when unlabeled
+    pub fn sender_name(&self) -> Cow<'_, str> {
+        display_name(&self.from_label, &self.from_ens, self.from_kind)
+    }
+
+    /// Recipient label, or its ENS name or kind when unlabeled
+    pub fn recipient_name(&self) -> Cow<'_, str> {
+        display_name(&self.to_label, &self.to_ens, self.to_kind)
+    }
+
+    /// Check whether either side of the transfer is in a category
+    pub fn involves(&self, category: LabelCategory) -> bool {
+        self.from_category == Some(category) || self.to_category == Some(category)
     }
 
     /// Get formatted amount with thousands separator
     pub fn formatted_amount(&self) -> String {
-        let formatted = format_with_commas(self.amount_usd);
-        format!("${} USDC", formatted)
+        i18n::messages().amount(self.amount_usd)
+    }
+
+    /// Get formatted fiat amounts, e.g. `≈ ₩2,070,000,000 · €1,380,000`
+    pub fn formatted_fiat(&self) -> Option<String> {
+        if self.fiat.is_empty() {
+            return None;
+        }
+        Some(format!("≈ {}", format_fiat(&self.fiat)))
+    }
+
+    /// Amount with fiat conversions, e.g. `$1,500,000.00 USDC (≈ ₩2,070,000,000)`
+    pub fn amount_text(&self) -> String {
+        match self.formatted_fiat() {
+            Some(fiat) => format!("{} ({})", self.formatted_amount(), fiat),
+            None => self.formatted_amount(),
+        }
     }
 
     /// Get short transaction hash
@@ -141,8 +349,58 @@ impl WhaleTransfer {
     }
 }
 
+/// Shorten an address to `0x12345678...9abcdef0`
+pub fn short_address(address: &Address) -> String {
+    let addr_str = format!("{:?}", address);
+    format!("{}...{}", &addr_str[..10], &addr_str[addr_str.len() - 8..])
+}
+
+/// Label, ENS name, or a placeholder such as `Unknown Safe`
+///
+/// ENS names are chosen by the address owner, so they are tagged to keep
+/// them apart from curated labels.
+fn display_name<'a>(
+    label: &'a Option<String>,
+    ens: &'a Option<String>,
+    kind: Option<AddressKind>,
+) -> Cow<'a, str> {
+    if let Some(label) = label {
+        return Cow::Borrowed(label);
+    }
+    if let Some(name) = ens {
+        return Cow::Owned(format!("{} · ENS", name));
+    }
+    let messages = i18n::messages();
+    Cow::Borrowed(match kind {
+        Some(AddressKind::Safe) => messages.unknown_safe,
+        Some(AddressKind::Clone) => messages.unknown_clone,
+        Some(AddressKind::Contract) => messages.unknown_contract,
+        Some(AddressKind::Eoa) => messages.unknown_wallet,
+        None => messages.unknown,
+    })
+}
+
+/// Format fiat amounts rounded to whole units, e.g. `₩2,070,000,000 · €1,380,000`
+pub fn format_fiat(amounts: &[FiatAmount]) -> String {
+    amounts
+        .iter()
+        .map(|fiat| {
+            let value = format_with_commas(fiat.amount.round());
+            let value = value.trim_end_matches(".00");
+            match fiat.currency.as_str() {
+                "KRW" => format!("₩{}", value),
+                "EUR" => format!("€{}", value),
+                "JPY" => format!("¥{}", value),
+                "GBP" => format!("£{}", value),
+                currency => format!("{} {}", value, currency),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
 /// Format a number with commas as thousands separators
-fn format_with_commas(value: f64) -> String {
+pub fn format_with_commas(value: f64) -> String {
     let integer_part = value.trunc() as i64;
     let decimal_part = ((value.fract() * 100.0).round() as i64).abs();
 
@@ -151,7 +409,7 @@ fn format_with_commas(value: f64) -> String {
     let chars: Vec<char> = int_str.chars().collect();
 
     for (i, c) in chars.iter().enumerate() {
-        if i > 0 && (chars.len() - i) % 3 == 0 {
+        if i > 0 && (chars.len() - i).is_multiple_of(3) {
             result.push(',');
         }
         result.push(*c);
@@ -163,3 +421,30 @@ fn format_with_commas(value: f64) -> String {
 
     format!("{}.{:02}", result, decimal_part)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_names() {
+        let transfer = WhaleTransfer::new(
+            Chain::Ethereum,
+            B256::repeat_byte(1),
+            1,
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            U256::from(1u64),
+        )
+        .with_from_ens(Some("treasury.eth".to_string()))
+        .with_to_kind(Some(AddressKind::Safe));
+
+        assert_eq!(transfer.sender_name(), "treasury.eth · ENS");
+        assert_eq!(transfer.recipient_name(), i18n::messages().unknown_safe);
+        let wallet = transfer.clone().with_to_kind(Some(AddressKind::Eoa));
+        assert_eq!(wallet.recipient_name(), i18n::messages().unknown_wallet);
+
+        let labeled = transfer.with_from_label(Some("Treasury".to_string()));
+        assert_eq!(labeled.sender_name(), "Treasury");
+    }
+}